@@ -0,0 +1,175 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::solution::Answer;
+use crate::utils::input_resolver::InputResolver;
+
+/// The answers file lives next to the inputs it describes.
+pub fn answers_file() -> String {
+  format!("{}/answers.toml", InputResolver::current().directory())
+}
+
+/// Known-correct answers keyed by day, input and part, stored as a small TOML file:
+///
+/// ```toml
+/// [day1."day1part1.txt"]
+/// part1 = 55607
+/// part2 = 55291
+/// ```
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct AnswerStore {
+  answers: BTreeMap<(u8, String, u8), Answer>,
+}
+
+impl AnswerStore {
+  pub fn load<P>(filename: P) -> Result<Self, String>
+  where P: AsRef<Path>, {
+    match fs::read_to_string(&filename) {
+      Ok(contents) => Self::parse(&contents),
+      Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+      Err(error) => Err(format!("Could not read {}: {}", filename.as_ref().display(), error)),
+    }
+  }
+
+  pub fn save<P>(self: &Self, filename: P) -> io::Result<()>
+  where P: AsRef<Path>, {
+    fs::write(filename, self.to_toml())
+  }
+
+  pub fn get(self: &Self, day: u8, input: &str, part: u8) -> Option<&Answer> {
+    self.answers.get(&(day, input_key(input), part))
+  }
+
+  pub fn insert(self: &mut Self, day: u8, input: &str, part: u8, answer: Answer) {
+    self.answers.insert((day, input_key(input), part), answer);
+  }
+
+  /// Every input with a recorded answer for the given day, as paths the runner can open.
+  pub fn inputs_for_day(self: &Self, day: u8) -> Vec<String> {
+    let mut inputs: Vec<String> = self.answers.keys()
+      .filter(|(answer_day, _, _)| *answer_day == day)
+      .map(|(_, input, _)| input_path(input))
+      .collect();
+
+    inputs.dedup();
+    inputs
+  }
+
+  fn parse(contents: &str) -> Result<Self, String> {
+    let mut store = Self::default();
+    let mut section: Option<(u8, String)> = None;
+
+    for (index, line) in contents.lines().enumerate() {
+      let line_number = index + 1;
+      let line = line.split('#').next().unwrap_or("").trim();
+
+      if line.is_empty() {
+        continue;
+      }
+
+      if let Some(header) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+        section = Some(parse_header(header).ok_or_else(|| format!("line {}: invalid section header '{}'", line_number, line))?);
+        continue;
+      }
+
+      let (day, input) = section.as_ref().ok_or_else(|| format!("line {}: answer outside of a [dayN.\"input\"] section", line_number))?;
+
+      let (key, value) = line.split_once('=').ok_or_else(|| format!("line {}: expected 'partN = answer'", line_number))?;
+
+      let part = match key.trim() {
+        "part1" => 1,
+        "part2" => 2,
+        other => return Err(format!("line {}: unknown key '{}'", line_number, other)),
+      };
+
+      let answer = parse_value(value.trim()).ok_or_else(|| format!("line {}: invalid answer '{}'", line_number, value.trim()))?;
+
+      store.answers.insert((*day, input.clone(), part), answer);
+    }
+
+    Ok(store)
+  }
+
+  fn to_toml(self: &Self) -> String {
+    let mut toml = String::new();
+    let mut section: Option<(u8, &String)> = None;
+
+    for ((day, input, part), answer) in self.answers.iter() {
+      if section != Some((*day, input)) {
+        if section.is_some() {
+          toml.push('\n');
+        }
+        toml.push_str(&format!("[day{}.\"{}\"]\n", day, input));
+        section = Some((*day, input));
+      }
+
+      let value = match answer {
+        Answer::Number(number) => number.to_string(),
+        Answer::Text(text) => format!("\"{}\"", text),
+        Answer::Unsolved => continue,
+      };
+
+      toml.push_str(&format!("part{} = {}\n", part, value));
+    }
+
+    toml
+  }
+}
+
+/// Inputs in the input directory are stored by file name, anything else by the path given.
+fn input_key(input: &str) -> String {
+  InputResolver::current().relative(input).to_string()
+}
+
+fn input_path(key: &str) -> String {
+  if key.contains('/') {
+    return key.to_string();
+  }
+
+  format!("{}/{}", InputResolver::current().directory(), key)
+}
+
+fn parse_header(header: &str) -> Option<(u8, String)> {
+  let (day, input) = header.split_once('.')?;
+  let day = day.trim().strip_prefix("day")?.parse::<u8>().ok()?;
+  let input = input.trim().strip_prefix('"')?.strip_suffix('"')?;
+
+  Some((day, input.to_string()))
+}
+
+fn parse_value(value: &str) -> Option<Answer> {
+  if let Some(text) = value.strip_prefix('"').and_then(|v| v.strip_suffix('"')) {
+    return Some(Answer::Text(text.to_string()));
+  }
+
+  value.parse::<i64>().ok().map(Answer::Number)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn store_round_trips_through_toml() {
+    let mut store = AnswerStore::default();
+    store.insert(1, "./inputs/day1part1.txt", 1, Answer::Number(55607));
+    store.insert(1, "./inputs/day1part1.txt", 2, Answer::Number(55291));
+    store.insert(9, "./other/input.txt", 1, Answer::Text("ABC".to_string()));
+
+    let toml = store.to_toml();
+    assert_eq!(toml, "[day1.\"day1part1.txt\"]\npart1 = 55607\npart2 = 55291\n\n[day9.\"./other/input.txt\"]\npart1 = \"ABC\"\n");
+
+    let parsed = AnswerStore::parse(&toml).unwrap();
+    assert_eq!(parsed, store);
+    assert_eq!(parsed.get(1, "inputs/day1part1.txt", 2), Some(&Answer::Number(55291)));
+    assert_eq!(parsed.inputs_for_day(9), vec!["./other/input.txt".to_string()]);
+  }
+
+  #[test]
+  fn invalid_lines_report_their_line_number() {
+    assert_eq!(AnswerStore::parse("part1 = 3"), Err("line 1: answer outside of a [dayN.\"input\"] section".to_string()));
+    assert_eq!(AnswerStore::parse("# comment\n[day1.\"a.txt\"]\npart3 = 1"), Err("line 3: unknown key 'part3'".to_string()));
+  }
+}
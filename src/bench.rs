@@ -0,0 +1,296 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::cli::{BenchOptions, RunOptions};
+use crate::solution::RegisteredDay;
+use crate::utils::duration_utils::format_duration;
+use crate::utils::error::AocError;
+use crate::utils::json::JsonValue;
+
+pub const DEFAULT_BASELINE_FILE: &str = "./bench_baseline.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+  pub min: Duration,
+  pub median: Duration,
+  pub max: Duration,
+}
+
+impl Stats {
+  pub fn from_samples(samples: &[Duration]) -> Self {
+    let mut samples = samples.to_vec();
+    samples.sort();
+
+    let middle = samples.len() / 2;
+    let median = if samples.len().is_multiple_of(2) {
+      (samples[middle - 1] + samples[middle]) / 2
+    } else {
+      samples[middle]
+    };
+
+    Stats { min: samples[0], median, max: samples[samples.len() - 1] }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartBenchmark {
+  pub day: u8,
+  pub part: u8,
+  pub parse: Stats,
+  pub solve: Stats,
+}
+
+impl PartBenchmark {
+  fn total_median(self: &Self) -> Duration {
+    self.parse.median + self.solve.median
+  }
+}
+
+/// One part's medians on one input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct BaselineEntry {
+  day: u8,
+  part: u8,
+  /// Which input was timed, see `input_label`.
+  input: String,
+  parse: Duration,
+  solve: Duration,
+}
+
+/// Median timings from earlier `bench --save-baseline` runs, kept apart per input so example
+/// and real timings are never compared.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Baseline {
+  entries: Vec<BaselineEntry>,
+}
+
+impl Baseline {
+  pub fn load<P>(filename: P) -> Result<Option<Self>, String>
+  where P: AsRef<Path>, {
+    let contents = match fs::read_to_string(&filename) {
+      Ok(contents) => contents,
+      Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(None),
+      Err(error) => return Err(format!("Could not read {}: {}", filename.as_ref().display(), error)),
+    };
+
+    let invalid = || format!("{} is not a valid benchmark baseline", filename.as_ref().display());
+
+    let json = JsonValue::from_str(&contents).map_err(|error| format!("{}: {}", invalid(), error))?;
+    let entries = json.get("entries").and_then(|entries| entries.as_array()).ok_or_else(invalid)?;
+
+    let mut baseline = Baseline::default();
+
+    for entry in entries {
+      let field = |name: &str| entry.get(name).and_then(|value| value.as_i64()).ok_or_else(invalid);
+
+      // Entries saved before the input was recorded could be for any input, so they are dropped
+      let Some(input) = entry.get("input").and_then(|input| input.as_str()) else {
+        continue;
+      };
+
+      baseline.entries.push(BaselineEntry {
+        day: field("day")? as u8,
+        part: field("part")? as u8,
+        input: input.to_string(),
+        parse: Duration::from_nanos(field("parse_median_ns")? as u64),
+        solve: Duration::from_nanos(field("solve_median_ns")? as u64),
+      });
+    }
+
+    Ok(Some(baseline))
+  }
+
+  pub fn save<P>(self: &Self, filename: P) -> io::Result<()>
+  where P: AsRef<Path>, {
+    let entries = self.entries.iter().map(|entry| {
+      JsonValue::Object(vec![
+        ("day".to_string(), entry.day.into()),
+        ("part".to_string(), entry.part.into()),
+        ("input".to_string(), entry.input.as_str().into()),
+        ("parse_median_ns".to_string(), (entry.parse.as_nanos() as u64).into()),
+        ("solve_median_ns".to_string(), (entry.solve.as_nanos() as u64).into()),
+      ])
+    }).collect();
+
+    let json = JsonValue::Object(vec![("entries".to_string(), JsonValue::Array(entries))]);
+
+    fs::write(filename, format!("{}\n", json))
+  }
+
+  /// Replaces the medians for each result's part and input, keeping every other entry.
+  fn record(self: &mut Self, results: &[(PartBenchmark, String)]) {
+    for (result, input) in results {
+      self.entries.retain(|entry| !(entry.day == result.day && entry.part == result.part && entry.input == *input));
+      self.entries.push(BaselineEntry {
+        day: result.day,
+        part: result.part,
+        input: input.clone(),
+        parse: result.parse.median,
+        solve: result.solve.median,
+      });
+    }
+  }
+
+  fn total_median(self: &Self, day: u8, part: u8, input: &str) -> Option<Duration> {
+    self.entries.iter()
+      .find(|entry| entry.day == day && entry.part == part && entry.input == input)
+      .map(|entry| entry.parse + entry.solve)
+  }
+}
+
+/// Names the input a part is benchmarked on: the input kind, or the file for an explicit `--input`.
+fn input_label(run: &RunOptions) -> String {
+  match &run.input {
+    Some(input) => format!("file:{}", input),
+    None => run.kind.name().to_string(),
+  }
+}
+
+/// Percentage change of `current` against `baseline`, positive when slower.
+fn percent_change(baseline: Duration, current: Duration) -> f64 {
+  if baseline.is_zero() {
+    return 0.0;
+  }
+
+  (current.as_secs_f64() / baseline.as_secs_f64() - 1.0) * 100.0
+}
+
+pub fn bench_part(registered: &RegisteredDay, part: u8, inputFile: Option<&str>, iterations: usize) -> Result<PartBenchmark, AocError> {
+  let mut parse_samples = vec![];
+  let mut solve_samples = vec![];
+
+  for _ in 0..iterations.max(1) {
+    let run = (registered.timed)(inputFile, &[part])?.remove(0);
+    parse_samples.push(run.parse);
+    solve_samples.push(run.solve);
+  }
+
+  Ok(PartBenchmark {
+    day: registered.day,
+    part,
+    parse: Stats::from_samples(&parse_samples),
+    solve: Stats::from_samples(&solve_samples),
+  })
+}
+
+/// Benchmarks every selected part, comparing against the baseline file if one exists.
+/// Returns an error message if the baseline could not be read or written.
+pub fn run(options: &BenchOptions, registry: &[RegisteredDay]) -> Result<(), String> {
+  let baseline = Baseline::load(&options.baseline)?;
+  let input = input_label(&options.run);
+
+  let stats = |stats: &Stats| format!("{} / {} / {}", format_duration(stats.min), format_duration(stats.median), format_duration(stats.max));
+
+  println!("Benchmarking {} iteration(s) per part, min / median / max", options.iterations);
+  println!("{:<5}{:<6}{:<36}{:<36}vs baseline", "Day", "Part", "Parse", "Solve");
+
+  let mut results = vec![];
+
+  for registered in registry.iter().filter(|registered| options.run.days.contains(registered.day)) {
+    for part in [1, 2] {
+      if !options.run.runs_part(part) {
+        continue;
+      }
+
+      let result = match bench_part(registered, part, Some(&options.run.input_for(registered.day)), options.iterations) {
+        Ok(result) => result,
+        Err(error) => {
+          eprintln!("{}", error);
+          continue;
+        }
+      };
+
+      let comparison = match baseline.as_ref().and_then(|baseline| baseline.total_median(result.day, result.part, &input)) {
+        None => "-".to_string(),
+        Some(previous) => {
+          let change = percent_change(previous, result.total_median());
+          let flag = if change > options.threshold as f64 { "  REGRESSION" } else { "" };
+          format!("{:+.1}%{}", change, flag)
+        }
+      };
+
+      println!("{:<5}{:<6}{:<36}{:<36}{}", result.day, result.part, stats(&result.parse), stats(&result.solve), comparison);
+
+      results.push((result, input.clone()));
+    }
+  }
+
+  // The first run on an input becomes its baseline, later ones only with --save-baseline
+  let missing: Vec<(PartBenchmark, String)> = results.iter()
+    .filter(|(result, input)| baseline.as_ref().is_none_or(|baseline| baseline.total_median(result.day, result.part, input).is_none()))
+    .cloned()
+    .collect();
+
+  if options.save_baseline || !missing.is_empty() {
+    let mut updated = baseline.unwrap_or_default();
+    updated.record(if options.save_baseline { &results } else { &missing });
+    updated.save(&options.baseline)
+      .map_err(|error| format!("Could not write {}: {}", options.baseline, error))?;
+
+    println!("Saved baseline to {}", options.baseline);
+  }
+
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn stats_use_the_middle_sample() {
+    let samples: Vec<Duration> = [5, 1, 3].iter().map(|ms| Duration::from_millis(*ms)).collect();
+    assert_eq!(Stats::from_samples(&samples), Stats {
+      min: Duration::from_millis(1),
+      median: Duration::from_millis(3),
+      max: Duration::from_millis(5),
+    });
+
+    let samples: Vec<Duration> = [4, 1, 2, 8].iter().map(|ms| Duration::from_millis(*ms)).collect();
+    assert_eq!(Stats::from_samples(&samples).median, Duration::from_millis(3));
+  }
+
+  #[test]
+  fn baseline_round_trips_through_json() {
+    let path = std::env::temp_dir().join(format!("aoc_baseline_{}.json", std::process::id()));
+
+    let entry = |input: &str, solve: u64| BaselineEntry {
+      day: 1, part: 2, input: input.to_string(), parse: Duration::from_nanos(150), solve: Duration::from_micros(solve),
+    };
+    let baseline = Baseline { entries: vec![entry("real", 20), entry("example", 1)] };
+    baseline.save(&path).unwrap();
+
+    let loaded = Baseline::load(&path).unwrap().unwrap();
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(loaded, baseline);
+    assert_eq!(loaded.total_median(1, 2, "real"), Some(Duration::from_nanos(20_150)));
+    assert_eq!(loaded.total_median(1, 2, "example"), Some(Duration::from_nanos(1_150)));
+    assert_eq!(loaded.total_median(1, 2, "large"), None);
+    assert_eq!(loaded.total_median(1, 1, "real"), None);
+  }
+
+  #[test]
+  fn recording_keeps_other_inputs() {
+    let stats = |ms: u64| Stats { min: Duration::from_millis(ms), median: Duration::from_millis(ms), max: Duration::from_millis(ms) };
+    let result = |ms: u64| PartBenchmark { day: 6, part: 2, parse: stats(0), solve: stats(ms) };
+
+    let mut baseline = Baseline::default();
+    baseline.record(&[(result(234), "real".to_string())]);
+    baseline.record(&[(result(1), "example".to_string())]);
+    baseline.record(&[(result(200), "real".to_string())]);
+
+    assert_eq!(baseline.entries.len(), 2);
+    assert_eq!(baseline.total_median(6, 2, "real"), Some(Duration::from_millis(200)));
+    assert_eq!(baseline.total_median(6, 2, "example"), Some(Duration::from_millis(1)));
+  }
+
+  #[test]
+  fn slower_runs_have_a_positive_change() {
+    assert_eq!(percent_change(Duration::from_millis(100), Duration::from_millis(150)).round(), 50.0);
+    assert_eq!(percent_change(Duration::from_millis(100), Duration::from_millis(50)).round(), -50.0);
+  }
+}
@@ -0,0 +1,620 @@
+use std::ops::RangeInclusive;
+use std::thread;
+use std::time::Duration;
+
+use crate::report::OutputFormat;
+use crate::utils::input_resolver::{InputKind, InputResolver, STDIN_INPUT};
+use crate::utils::rng::Rng;
+
+pub const USAGE: &str = "\
+Usage:
+  run --all
+  run --day <N> [--part <1|2>] [--input <file>]
+  run --days <N-M> [--part <1|2>]
+      [--parallel] [--jobs <N>]      run the selected parts on a thread pool
+      [--format <text|json|csv>]     print the results for scripts instead of people
+      [--trace]                      break each part's time down into parse, build and solve spans
+      built with --features alloc-stats, each answer is followed by what parsing and solving allocated
+  verify [--day <N> | --days <N-M>] [--part <1|2>] [--input <file>]
+  record --day <N> [--part <1|2>] [--input <file>]
+  bench [--day <N> | --days <N-M>] [--part <1|2>] [--input <file>] [--iterations <N>]
+        [--baseline <file>] [--save-baseline] [--threshold <percent>]
+  watch --day <N> [--part <1|2>] [--input <file>] [--interval <ms>] [--binary]
+        rerun the day whenever its inputs change, with --binary also when the executable is rebuilt
+  fetch --day <N> [--base-url <url>]
+        download the day's input into dayNpart1.txt unless it is already there
+  submit --day <N> --part <1|2> [--base-url <url>]
+        run the part on the real input and submit its answer, unless submissions.json rules it out
+  fuzz --day <N> [--part <1|2>] [--iterations <N>] [--seed <N>]
+        check the day against a naive solution on random inputs, shrinking any disagreement
+  repl --day <N> [--input <file>]
+        parse the day's input and query it, e.g. node AAA, card 17, trace seed 79 or cell 3,4
+  new-day <N>                        scaffold src/dayN.rs and its inputs, and register it
+
+Inputs:
+  --example | --variant <name>       use dayNtest.txt or dayN<name>.txt instead of dayNpart1.txt
+  --input-dir <dir>                  defaults to $AOC_INPUT_DIR, then ./inputs
+  --input -                          read the puzzle from stdin
+
+Config:
+  aoc.toml (or $AOC_CONFIG) may set session = \"...\" and base_url = \"http://localhost:<port>\",
+  overridden by $AOC_SESSION and $AOC_BASE_URL. fetch and submit need a base URL, and only
+  send the session cookie to a local proxy";
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Command {
+  Run(RunOptions),
+  Verify(RunOptions),
+  Record(RunOptions),
+  Bench(BenchOptions),
+  Watch(WatchOptions),
+  Fetch(FetchOptions),
+  Submit(SubmitOptions),
+  Fuzz(FuzzOptions),
+  Repl(RunOptions),
+  NewDay(u8),
+  Help,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum DaySelection {
+  All,
+  Range(RangeInclusive<u8>),
+}
+
+impl DaySelection {
+  pub fn contains(self: &Self, day: u8) -> bool {
+    match self {
+      DaySelection::All => true,
+      DaySelection::Range(range) => range.contains(&day),
+    }
+  }
+
+  /// The day if exactly one is selected.
+  pub fn single(self: &Self) -> Option<u8> {
+    match self {
+      DaySelection::Range(range) if range.start() == range.end() => Some(*range.start()),
+      _ => None,
+    }
+  }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct RunOptions {
+  pub days: DaySelection,
+  pub part: Option<u8>,
+  pub input: Option<String>,
+  pub kind: InputKind,
+  pub input_dir: Option<String>,
+  /// Number of worker threads when running in parallel, `None` runs parts one after another.
+  pub parallel: Option<usize>,
+  pub format: OutputFormat,
+  /// Print each part's span tree after its answer.
+  pub trace: bool,
+}
+
+impl Default for RunOptions {
+  fn default() -> Self {
+    RunOptions { days: DaySelection::All, part: None, input: None, kind: InputKind::Real, input_dir: None, parallel: None, format: OutputFormat::Text, trace: false }
+  }
+}
+
+impl RunOptions {
+  pub fn runs_part(self: &Self, part: u8) -> bool {
+    self.part.is_none_or(|selected| selected == part)
+  }
+
+  /// The explicit `--input` if given, otherwise the day's file for the selected input kind.
+  pub fn input_for(self: &Self, day: u8) -> String {
+    match &self.input {
+      Some(input) => input.clone(),
+      None => InputResolver::current().path(day, &self.kind),
+    }
+  }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct BenchOptions {
+  pub run: RunOptions,
+  pub iterations: usize,
+  pub baseline: String,
+  pub save_baseline: bool,
+  /// Percentage slowdown against the baseline median before a part is flagged.
+  pub threshold: u32,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct WatchOptions {
+  pub run: RunOptions,
+  /// How often the watched files are checked.
+  pub interval: Duration,
+  /// Also watch the executable and run each pass in a new process started from it, so a `cargo build` is picked up.
+  pub binary: bool,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct FetchOptions {
+  pub run: RunOptions,
+  /// Overrides the configured base URL for this run.
+  pub base_url: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct SubmitOptions {
+  pub run: RunOptions,
+  /// Overrides the configured base URL for this run.
+  pub base_url: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct FuzzOptions {
+  pub day: u8,
+  pub part: Option<u8>,
+  pub iterations: usize,
+  /// Seed of the first iteration, each later one adds one to it.
+  pub seed: u64,
+}
+
+/// Parses the arguments following the program name. No arguments runs every registered day.
+pub fn parse_args<I>(args: I) -> Result<Command, String>
+where I: IntoIterator<Item = String>, {
+  let mut args = args.into_iter();
+
+  let command = match args.next() {
+    None => return Ok(Command::Run(RunOptions::default())),
+    Some(command) => command,
+  };
+
+  match command.as_str() {
+    "run" => parse_parallel_run_options(args.collect()).map(Command::Run),
+    "verify" => parse_run_options(args, false).map(Command::Verify),
+    "record" => parse_run_options(args, true).map(Command::Record),
+    "bench" => parse_bench_options(args.collect()).map(Command::Bench),
+    "watch" => parse_watch_options(args.collect()).map(Command::Watch),
+    "fetch" => parse_fetch_options(args.collect()).map(Command::Fetch),
+    "submit" => parse_submit_options(args.collect()).map(Command::Submit),
+    "fuzz" => parse_fuzz_options(args.collect()).map(Command::Fuzz),
+    "repl" => parse_repl_options(args).map(Command::Repl),
+    "new-day" => parse_new_day(args.collect()).map(Command::NewDay),
+    "help" | "--help" | "-h" => Ok(Command::Help),
+    _ => Err(format!("Unknown command '{}'", command)),
+  }
+}
+
+fn parse_run_options(mut args: impl Iterator<Item = String>, require_day: bool) -> Result<RunOptions, String> {
+  let mut options = RunOptions::default();
+  let mut day_selected = false;
+
+  while let Some(arg) = args.next() {
+    match arg.as_str() {
+      "--all" => {
+        options.days = DaySelection::All;
+        day_selected = true;
+      },
+      "--day" => {
+        let day = parse_day(&next_value(&mut args, &arg)?)?;
+        options.days = DaySelection::Range(day..=day);
+        day_selected = true;
+      },
+      "--days" => {
+        options.days = parse_day_range(&next_value(&mut args, &arg)?)?;
+        day_selected = true;
+      },
+      "--part" => {
+        let part = next_value(&mut args, &arg)?;
+        options.part = match part.as_str() {
+          "1" => Some(1),
+          "2" => Some(2),
+          _ => return Err(format!("Part must be 1 or 2, got '{}'", part)),
+        };
+      },
+      "--input" => {
+        options.input = Some(next_value(&mut args, &arg)?);
+      },
+      "--example" => {
+        options.kind = InputKind::Example;
+      },
+      "--variant" => {
+        options.kind = next_value(&mut args, &arg)?.parse()?;
+      },
+      "--input-dir" => {
+        options.input_dir = Some(next_value(&mut args, &arg)?);
+      },
+      _ => return Err(format!("Unknown argument '{}'", arg)),
+    }
+  }
+
+  if require_day && !day_selected {
+    return Err("Expected one of --all, --day or --days".to_string());
+  }
+
+  if options.input.is_some() && options.days.single().is_none() {
+    return Err("--input can only be used with a single --day".to_string());
+  }
+
+  Ok(options)
+}
+
+fn parse_parallel_run_options(mut args: Vec<String>) -> Result<RunOptions, String> {
+  let jobs = take_value(&mut args, "--jobs")?
+    .map(|value| value.parse::<usize>().ok().filter(|n| *n > 0).ok_or_else(|| format!("Jobs must be a positive number, got '{}'", value)))
+    .transpose()?;
+  let parallel = take_switch(&mut args, "--parallel");
+  let format = take_value(&mut args, "--format")?.map(|value| value.parse()).transpose()?;
+  let trace = take_switch(&mut args, "--trace");
+
+  let mut options = parse_run_options(args.into_iter(), true)?;
+  options.format = format.unwrap_or_default();
+  options.trace = trace;
+
+  if parallel || jobs.is_some() {
+    let available = thread::available_parallelism().map(|n| n.get()).unwrap_or(4);
+    options.parallel = Some(jobs.unwrap_or(available));
+  }
+
+  Ok(options)
+}
+
+fn parse_bench_options(mut args: Vec<String>) -> Result<BenchOptions, String> {
+  let iterations = take_value(&mut args, "--iterations")?
+    .map(|value| value.parse::<usize>().ok().filter(|n| *n > 0).ok_or_else(|| format!("Iterations must be a positive number, got '{}'", value)))
+    .transpose()?
+    .unwrap_or(10);
+
+  let threshold = take_value(&mut args, "--threshold")?
+    .map(|value| value.parse::<u32>().map_err(|_| format!("Threshold must be a whole percentage, got '{}'", value)))
+    .transpose()?
+    .unwrap_or(25);
+
+  let baseline = take_value(&mut args, "--baseline")?.unwrap_or_else(|| crate::bench::DEFAULT_BASELINE_FILE.to_string());
+  let save_baseline = take_switch(&mut args, "--save-baseline");
+
+  Ok(BenchOptions {
+    run: parse_run_options(args.into_iter(), false)?,
+    iterations,
+    baseline,
+    save_baseline,
+    threshold,
+  })
+}
+
+fn parse_watch_options(mut args: Vec<String>) -> Result<WatchOptions, String> {
+  let interval = take_value(&mut args, "--interval")?
+    .map(|value| value.parse::<u64>().ok().filter(|ms| *ms > 0).ok_or_else(|| format!("Interval must be a positive number of milliseconds, got '{}'", value)))
+    .transpose()?
+    .unwrap_or(500);
+  let binary = take_switch(&mut args, "--binary");
+
+  let run = parse_run_options(args.into_iter(), true)?;
+
+  if run.days.single().is_none() {
+    return Err("watch needs a single --day".to_string());
+  }
+
+  if run.input.as_deref() == Some(STDIN_INPUT) {
+    return Err("watch can't rerun on stdin, pass an input file instead".to_string());
+  }
+
+  Ok(WatchOptions { run, interval: Duration::from_millis(interval), binary })
+}
+
+fn parse_fetch_options(mut args: Vec<String>) -> Result<FetchOptions, String> {
+  let base_url = take_value(&mut args, "--base-url")?;
+
+  let run = parse_run_options(args.into_iter(), true)?;
+
+  if run.days.single().is_none() {
+    return Err("fetch needs a single --day".to_string());
+  }
+
+  if run.part.is_some() {
+    return Err("fetch downloads the whole day's input, --part has no meaning here".to_string());
+  }
+
+  if run.input.is_some() || run.kind != InputKind::Real {
+    return Err("fetch always downloads the real input into the input directory".to_string());
+  }
+
+  Ok(FetchOptions { run, base_url })
+}
+
+fn parse_submit_options(mut args: Vec<String>) -> Result<SubmitOptions, String> {
+  let base_url = take_value(&mut args, "--base-url")?;
+
+  let run = parse_run_options(args.into_iter(), true)?;
+
+  if run.days.single().is_none() || run.part.is_none() {
+    return Err("submit needs a single --day and --part".to_string());
+  }
+
+  if run.kind != InputKind::Real || run.input.is_some() || run.input_dir.is_some() {
+    return Err("submit always runs on the real input, --example, --variant, --input and --input-dir aren't allowed".to_string());
+  }
+
+  Ok(SubmitOptions { run, base_url })
+}
+
+fn parse_fuzz_options(mut args: Vec<String>) -> Result<FuzzOptions, String> {
+  let iterations = take_value(&mut args, "--iterations")?
+    .map(|value| value.parse::<usize>().ok().filter(|n| *n > 0).ok_or_else(|| format!("Iterations must be a positive number, got '{}'", value)))
+    .transpose()?
+    .unwrap_or(100);
+  let seed = take_value(&mut args, "--seed")?
+    .map(|value| value.parse::<u64>().map_err(|_| format!("Seed must be a number, got '{}'", value)))
+    .transpose()?
+    .unwrap_or_else(Rng::time_seed);
+
+  let run = parse_run_options(args.into_iter(), true)?;
+
+  let Some(day) = run.days.single() else {
+    return Err("fuzz needs a single --day".to_string());
+  };
+
+  if run.input.is_some() || run.kind != InputKind::Real || run.input_dir.is_some() {
+    return Err("fuzz generates its own inputs".to_string());
+  }
+
+  Ok(FuzzOptions { day, part: run.part, iterations, seed })
+}
+
+fn parse_repl_options(args: impl Iterator<Item = String>) -> Result<RunOptions, String> {
+  let run = parse_run_options(args, true)?;
+
+  if run.days.single().is_none() {
+    return Err("repl needs a single --day".to_string());
+  }
+
+  if run.input.as_deref() == Some(STDIN_INPUT) {
+    return Err("repl reads its queries from stdin, pass an input file instead".to_string());
+  }
+
+  Ok(run)
+}
+
+fn parse_new_day(args: Vec<String>) -> Result<u8, String> {
+  match args.as_slice() {
+    [day] => parse_day(day),
+    _ => Err("Expected a single day number, e.g. new-day 9".to_string()),
+  }
+}
+
+/// Removes `flag` and the value following it, leaving the remaining arguments for another parser.
+fn take_value(args: &mut Vec<String>, flag: &str) -> Result<Option<String>, String> {
+  let Some(index) = args.iter().position(|arg| arg == flag) else {
+    return Ok(None);
+  };
+
+  if index + 1 >= args.len() {
+    return Err(format!("Missing value for {}", flag));
+  }
+
+  let value = args.remove(index + 1);
+  args.remove(index);
+
+  Ok(Some(value))
+}
+
+fn take_switch(args: &mut Vec<String>, flag: &str) -> bool {
+  let Some(index) = args.iter().position(|arg| arg == flag) else {
+    return false;
+  };
+
+  args.remove(index);
+  true
+}
+
+fn next_value(args: &mut impl Iterator<Item = String>, flag: &str) -> Result<String, String> {
+  args.next().ok_or_else(|| format!("Missing value for {}", flag))
+}
+
+fn parse_day(value: &str) -> Result<u8, String> {
+  match value.parse::<u8>() {
+    Ok(day) if (1..=25).contains(&day) => Ok(day),
+    _ => Err(format!("Day must be between 1 and 25, got '{}'", value)),
+  }
+}
+
+fn parse_day_range(value: &str) -> Result<DaySelection, String> {
+  let (start, end) = value.split_once('-').ok_or_else(|| format!("Day range must look like 3-7, got '{}'", value))?;
+
+  let start = parse_day(start)?;
+  let end = parse_day(end)?;
+
+  if start > end {
+    return Err(format!("Day range {} is backwards", value));
+  }
+
+  Ok(DaySelection::Range(start..=end))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn parse(args: &str) -> Result<Command, String> {
+    parse_args(args.split_whitespace().map(String::from))
+  }
+
+  #[test]
+  fn no_arguments_runs_everything() {
+    assert_eq!(parse(""), Ok(Command::Run(RunOptions::default())));
+  }
+
+  #[test]
+  fn single_day_part_and_input() {
+    let expected = RunOptions {
+      days: DaySelection::Range(5..=5),
+      part: Some(2),
+      input: Some("path/to/file".to_string()),
+      ..RunOptions::default()
+    };
+
+    assert_eq!(parse("run --day 5 --part 2 --input path/to/file"), Ok(Command::Run(expected)));
+  }
+
+  #[test]
+  fn day_ranges() {
+    let options = match parse("run --days 3-7") {
+      Ok(Command::Run(options)) => options,
+      other => panic!("Unexpected parse result {:?}", other),
+    };
+
+    assert!(!options.days.contains(2));
+    assert!(options.days.contains(3));
+    assert!(options.days.contains(7));
+    assert!(!options.days.contains(8));
+    assert!(options.runs_part(1) && options.runs_part(2));
+  }
+
+  #[test]
+  fn invalid_arguments_are_rejected() {
+    assert!(parse("run").is_err());
+    assert!(parse("run --day 26").is_err());
+    assert!(parse("run --days 7-3").is_err());
+    assert!(parse("run --day 5 --part 3").is_err());
+    assert!(parse("run --days 3-7 --input file.txt").is_err());
+    assert!(parse("walk --all").is_err());
+  }
+
+  #[test]
+  fn parallel_runs() {
+    let options = match parse("run --all --jobs 3") {
+      Ok(Command::Run(options)) => options,
+      other => panic!("Unexpected parse result {:?}", other),
+    };
+    assert_eq!(options.parallel, Some(3));
+
+    let options = match parse("run --days 1-4 --parallel") {
+      Ok(Command::Run(options)) => options,
+      other => panic!("Unexpected parse result {:?}", other),
+    };
+    assert!(options.parallel.is_some_and(|jobs| jobs > 0));
+
+    assert!(parse("run --all --jobs 0").is_err());
+  }
+
+  #[test]
+  fn output_formats() {
+    let options = match parse("run --all --format csv --jobs 2") {
+      Ok(Command::Run(options)) => options,
+      other => panic!("Unexpected parse result {:?}", other),
+    };
+    assert_eq!(options.format, OutputFormat::Csv);
+    assert!(!options.trace);
+
+    let options = match parse("run --day 8 --trace") {
+      Ok(Command::Run(options)) => options,
+      other => panic!("Unexpected parse result {:?}", other),
+    };
+    assert!(options.trace);
+
+    assert!(parse("run --all --format yaml").is_err());
+    assert!(parse("verify --format json").is_err());
+  }
+
+  #[test]
+  fn input_kinds_and_directory() {
+    let options = match parse("run --days 6-8 --example --input-dir ./other") {
+      Ok(Command::Run(options)) => options,
+      other => panic!("Unexpected parse result {:?}", other),
+    };
+    assert_eq!(options.kind, InputKind::Example);
+    assert_eq!(options.input_dir.as_deref(), Some("./other"));
+
+    let options = match parse("run --day 5 --variant large") {
+      Ok(Command::Run(options)) => options,
+      other => panic!("Unexpected parse result {:?}", other),
+    };
+    assert_eq!(options.kind, InputKind::Named("large".to_string()));
+
+    assert!(parse("run --day 5 --variant ../x").is_err());
+  }
+
+  #[test]
+  fn verify_defaults_to_every_day() {
+    assert_eq!(parse("verify"), Ok(Command::Verify(RunOptions::default())));
+    assert!(parse("record").is_err());
+  }
+
+  #[test]
+  fn watch_options() {
+    let expected = WatchOptions {
+      run: RunOptions { days: DaySelection::Range(8..=8), kind: InputKind::Example, ..RunOptions::default() },
+      interval: Duration::from_millis(200),
+      binary: true,
+    };
+
+    assert_eq!(parse("watch --day 8 --example --interval 200 --binary"), Ok(Command::Watch(expected)));
+    assert!(parse("watch --days 1-3").is_err());
+    assert!(parse("watch --day 3 --input -").is_err());
+  }
+
+  #[test]
+  fn fetch_options() {
+    let expected = FetchOptions {
+      run: RunOptions { days: DaySelection::Range(9..=9), ..RunOptions::default() },
+      base_url: Some("http://localhost:8080".to_string()),
+    };
+
+    assert_eq!(parse("fetch --base-url http://localhost:8080 --day 9"), Ok(Command::Fetch(expected)));
+    assert!(parse("fetch --all").is_err());
+    assert!(parse("fetch --day 9 --part 1").is_err());
+    assert!(parse("fetch --day 9 --input day9.txt").is_err());
+    assert!(parse("fetch --day 9 --example").is_err());
+    assert!(parse("fetch --day 9 --input-dir elsewhere").is_ok());
+  }
+
+  #[test]
+  fn submit_needs_a_day_and_part() {
+    let expected = SubmitOptions {
+      run: RunOptions { days: DaySelection::Range(5..=5), part: Some(2), ..RunOptions::default() },
+      base_url: None,
+    };
+
+    assert_eq!(parse("submit --day 5 --part 2"), Ok(Command::Submit(expected)));
+    assert!(parse("submit --day 5").is_err());
+    assert!(parse("submit --day 5 --part 1 --example").is_err());
+    assert!(parse("submit --day 5 --part 1 --input other.txt").is_err());
+    assert!(parse("submit --day 5 --part 1 --input-dir elsewhere").is_err());
+  }
+
+  #[test]
+  fn fuzz_options() {
+    assert_eq!(parse("fuzz --day 7 --part 2 --iterations 20 --seed 5"), Ok(Command::Fuzz(FuzzOptions { day: 7, part: Some(2), iterations: 20, seed: 5 })));
+    assert!(matches!(parse("fuzz --day 4"), Ok(Command::Fuzz(FuzzOptions { iterations: 100, .. }))));
+    assert!(parse("fuzz --days 4-5").is_err());
+    assert!(parse("fuzz --day 4 --example").is_err());
+  }
+
+  #[test]
+  fn repl_options() {
+    let expected = RunOptions { days: DaySelection::Range(8..=8), kind: InputKind::Example, ..RunOptions::default() };
+
+    assert_eq!(parse("repl --day 8 --example"), Ok(Command::Repl(expected)));
+    assert!(parse("repl --all").is_err());
+    assert!(parse("repl --day 8 --input -").is_err());
+  }
+
+  #[test]
+  fn new_day_takes_a_single_day() {
+    assert_eq!(parse("new-day 9"), Ok(Command::NewDay(9)));
+    assert!(parse("new-day").is_err());
+    assert!(parse("new-day 26").is_err());
+    assert!(parse("new-day 9 10").is_err());
+  }
+
+  #[test]
+  fn bench_options() {
+    let expected = BenchOptions {
+      run: RunOptions { days: DaySelection::Range(5..=5), part: Some(1), ..RunOptions::default() },
+      iterations: 3,
+      baseline: "base.json".to_string(),
+      save_baseline: true,
+      threshold: 25,
+    };
+
+    assert_eq!(parse("bench --day 5 --iterations 3 --part 1 --save-baseline --baseline base.json --threshold 25"), Ok(Command::Bench(expected)));
+    assert!(parse("bench --iterations 0").is_err());
+    assert!(parse("bench --iterations").is_err());
+  }
+}
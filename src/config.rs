@@ -0,0 +1,100 @@
+use std::env;
+use std::fs;
+use std::io;
+
+pub const CONFIG_FILE_ENV: &str = "AOC_CONFIG";
+pub const DEFAULT_CONFIG_FILE: &str = "./aoc.toml";
+
+pub const SESSION_ENV: &str = "AOC_SESSION";
+pub const BASE_URL_ENV: &str = "AOC_BASE_URL";
+
+pub const YEAR: u16 = 2023;
+
+/// Settings for talking to the puzzle site, read from `aoc.toml` and overridden by the environment.
+/// There is no default base URL: requests go over plain http, so they have to go through a local
+/// proxy rather than straight to the site.
+///
+/// ```toml
+/// session = "53616c746564..."
+/// base_url = "http://localhost:8080"
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Config {
+  pub session: Option<String>,
+  pub base_url: Option<String>,
+}
+
+impl Config {
+  /// Reads the config file (`$AOC_CONFIG`, then `./aoc.toml`), then applies `AOC_SESSION` and `AOC_BASE_URL`.
+  /// A missing config file is the same as an empty one.
+  pub fn load() -> Result<Self, String> {
+    let filename = env::var(CONFIG_FILE_ENV).unwrap_or_else(|_| DEFAULT_CONFIG_FILE.to_string());
+
+    let mut config = match fs::read_to_string(&filename) {
+      Ok(contents) => Self::parse(&contents).map_err(|message| format!("{}: {}", filename, message))?,
+      Err(error) if error.kind() == io::ErrorKind::NotFound => Self::default(),
+      Err(error) => return Err(format!("Could not read {}: {}", filename, error)),
+    };
+
+    if let Some(session) = env::var(SESSION_ENV).ok().filter(|session| !session.is_empty()) {
+      config.session = Some(session);
+    }
+
+    if let Some(base_url) = env::var(BASE_URL_ENV).ok().filter(|base_url| !base_url.is_empty()) {
+      config.base_url = Some(base_url);
+    }
+
+    Ok(config)
+  }
+
+  pub fn base_url(self: &Self) -> Result<&str, String> {
+    self.base_url.as_deref()
+      .ok_or_else(|| format!("No base URL, set {} or add base_url = \"http://localhost:<port>\" to {}", BASE_URL_ENV, DEFAULT_CONFIG_FILE))
+  }
+
+  pub fn session(self: &Self) -> Result<&str, String> {
+    self.session.as_deref()
+      .ok_or_else(|| format!("No session cookie, set {} or add session = \"...\" to {}", SESSION_ENV, DEFAULT_CONFIG_FILE))
+  }
+
+  fn parse(contents: &str) -> Result<Self, String> {
+    let mut config = Self::default();
+
+    for (index, line) in contents.lines().enumerate() {
+      let line = line.trim();
+
+      if line.is_empty() || line.starts_with('#') {
+        continue;
+      }
+
+      let (key, value) = line.split_once('=').ok_or_else(|| format!("line {}: expected 'key = \"value\"'", index + 1))?;
+      let value = value.trim().strip_prefix('"').and_then(|value| value.strip_suffix('"'))
+        .ok_or_else(|| format!("line {}: values must be quoted", index + 1))?
+        .to_string();
+
+      match key.trim() {
+        "session" => config.session = Some(value),
+        "base_url" => config.base_url = Some(value),
+        other => return Err(format!("line {}: unknown key '{}'", index + 1, other)),
+      }
+    }
+
+    Ok(config)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn config_reads_quoted_values() {
+    let config = Config::parse("# local stand-in\nsession = \"abc123\"\nbase_url = \"http://localhost:8080\"\n").unwrap();
+
+    assert_eq!(config.session(), Ok("abc123"));
+    assert_eq!(config.base_url(), Ok("http://localhost:8080"));
+    assert!(Config::default().base_url().is_err());
+    assert!(Config::default().session().is_err());
+    assert_eq!(Config::parse("token = \"x\""), Err("line 1: unknown key 'token'".to_string()));
+  }
+}
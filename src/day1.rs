@@ -1,49 +1,60 @@
-use crate::utils::file_utils;
+use crate::solution::{self, Answer, Solution};
+use crate::utils::error::AocError;
 
-pub fn part_1(inputFile: Option<&str>) {
+pub struct Day1;
 
-  let inputFile = inputFile.unwrap_or("./inputs/day1part1.txt");
+impl Solution for Day1 {
+  const DAY: u8 = 1;
 
-  if let Ok(lines) = file_utils::read_lines(inputFile) {
+  /// The whole input, kept so a line without digits can be reported where it is.
+  type Parsed<'a> = &'a str;
+
+  fn parse(input: &str) -> Result<Self::Parsed<'_>, AocError> {
+    Ok(input)
+  }
+
+  fn part_1(input: &Self::Parsed<'_>) -> Result<Answer, AocError> {
 
     let mut calibration_sum: u32 = 0;
 
-    for line in lines.flatten() {
+    for line in input.lines() {
 
-      let calibration_value = extract_calibration_value(&line);
+      let calibration_value = extract_calibration_value(input, line, line)?;
       
-      calibration_sum = calibration_sum + calibration_value;
+      calibration_sum += calibration_value;
     
     }
 
-    println!("Day 1 Part 1: {}", calibration_sum);
+    Ok(calibration_sum.into())
   }
-}
 
-pub fn part_2(inputFile: Option<&str>) {
-
-  let inputFile = inputFile.unwrap_or("./inputs/day1part1.txt");
-
-  if let Ok(lines) = file_utils::read_lines(inputFile) {
+  fn part_2(input: &Self::Parsed<'_>) -> Result<Answer, AocError> {
 
     let mut calibration_sum: u32 = 0;
 
-    for mut line in lines.flatten() {
+    for line in input.lines() {
 
-      let line = parse_numerics_from_string(&mut line);
+      let digits = parse_numerics_from_string(line);
 
-      let calibration_value = extract_calibration_value(&line);
+      let calibration_value = extract_calibration_value(input, line, &digits)?;
       
-      calibration_sum = calibration_sum + calibration_value;
+      calibration_sum += calibration_value;
     
     }
 
-    println!("Day 1 Part 2: {}", calibration_sum);
+    Ok(calibration_sum.into())
   }
+}
 
+pub fn part_1(inputFile: Option<&str>) -> Result<Answer, AocError> {
+  solution::run_part_1::<Day1>(inputFile)
 }
 
-fn parse_numerics_from_string(line: &mut String) -> String {
+pub fn part_2(inputFile: Option<&str>) -> Result<Answer, AocError> {
+  solution::run_part_2::<Day1>(inputFile)
+}
+
+fn parse_numerics_from_string(line: &str) -> String {
 
   let numeric_words = [
     ("zero", '0'),
@@ -84,25 +95,27 @@ fn parse_numerics_from_string(line: &mut String) -> String {
   return lineNumbers;
 }
 
-fn extract_calibration_value(line: &String) -> u32 {
+/// The first and last digit in `digits`, read from `line` of `input`, as a two digit number.
+fn extract_calibration_value(input: &str, line: &str, digits: &str) -> Result<u32, AocError> {
 
-  let mut firstDigit: Option<char> = None;
-  let mut lastDigit: Option<char> = None;
+  let mut firstDigit: Option<u32> = None;
+  let mut lastDigit: Option<u32> = None;
 
-  for char in line.chars() {
-    if let Some(_) = char.to_digit(10) {
+  for char in digits.chars() {
+    if let Some(digit) = char.to_digit(10) {
 
       if firstDigit.is_none() {
-        firstDigit = Some(char.clone());
+        firstDigit = Some(digit);
       }
-      lastDigit = Some(char.clone());
+      lastDigit = Some(digit);
     }
   }
 
-  let mut calibration_value = String::from(firstDigit.unwrap());
-  calibration_value.push(lastDigit.unwrap());
+  let (Some(firstDigit), Some(lastDigit)) = (firstDigit, lastDigit) else {
+    return Err(AocError::new("Expected a digit on this line", input, line));
+  };
 
-  calibration_value.parse().unwrap()
+  Ok(firstDigit * 10 + lastDigit)
 }
 
 #[cfg(test)]
@@ -110,25 +123,39 @@ mod tests {
 
 use super::*;
 
+  fn calibration_value(line: &str) -> u32 {
+    extract_calibration_value(line, line, line).unwrap()
+  }
+
   #[test]
   fn test_extract_calibration_value() {
-    assert_eq!(extract_calibration_value(&String::from("abc123def")), 13);
-    assert_eq!(extract_calibration_value(&String::from("abc1234def")), 14);
-    assert_eq!(extract_calibration_value(&String::from("abc12345def")), 15);
+    assert_eq!(calibration_value("abc123def"), 13);
+    assert_eq!(calibration_value("abc1234def"), 14);
+    assert_eq!(calibration_value("abc12345def"), 15);
+  }
+
+  #[test]
+  fn lines_without_digits_are_reported() {
+    let input = "1abc2\nxyz\n";
+    let error = Day1::part_1(&Day1::parse(input).unwrap()).unwrap_err();
+
+    assert_eq!(error.message, "Expected a digit on this line");
+    assert_eq!((error.line, error.column, error.text.as_str()), (2, 1, "xyz"));
+    assert_eq!(Day1::part_2(&"one\n"), Ok(Answer::Number(11)));
   }
 
   #[test]
   fn test_convert_numeric_words_ordered_first_to_last()  {
-    assert_eq!(parse_numerics_from_string(&mut String::from("zoneight234")), "18234");
-    assert_eq!(parse_numerics_from_string(&mut String::from("zeightwo234")), "82234");
+    assert_eq!(parse_numerics_from_string(&String::from("zoneight234")), "18234");
+    assert_eq!(parse_numerics_from_string(&String::from("zeightwo234")), "82234");
   }  
 
   #[test]
   fn test_convert_numeric_words() {
-    let mut line = String::from("one two three four five six seven eight nine zero");
-    assert_eq!(parse_numerics_from_string(&mut line), "1234567890");
-    assert_eq!(parse_numerics_from_string(&mut String::from("abc123defour")), "1234");
-    assert_eq!(parse_numerics_from_string(&mut String::from("abc1234dthreefourthree")), "1234343");
-    assert_eq!(parse_numerics_from_string(&mut String::from("abtwo12345defour")), "2123454");
+    let line = String::from("one two three four five six seven eight nine zero");
+    assert_eq!(parse_numerics_from_string(&line), "1234567890");
+    assert_eq!(parse_numerics_from_string(&String::from("abc123defour")), "1234");
+    assert_eq!(parse_numerics_from_string(&String::from("abc1234dthreefourthree")), "1234343");
+    assert_eq!(parse_numerics_from_string(&String::from("abtwo12345defour")), "2123454");
   }
 }
\ No newline at end of file
@@ -1,49 +1,61 @@
-use crate::utils::file_utils;
+use crate::solution::{self, Answer, Solution};
+use crate::utils::error::AocError;
+use crate::utils::parser::{self, alt, delimited, literal, map, pair, separated, spaces, terminated, unsigned, ParseResult};
 use std::str::FromStr;
 
-pub fn part_1(inputFile: Option<&str>) {
-  let inputFile = inputFile.unwrap_or("./inputs/day2part1.txt");
+pub struct Day2;
 
-  let maxGreen = 13;
-  let maxRed = 12;
-  let maxBlue = 14;
+impl Solution for Day2 {
+  const DAY: u8 = 2;
 
-  let mut possibleGameIdSum = 0;
+  type Parsed<'a> = Vec<GameRound>;
 
-  if let Ok(lines) = file_utils::read_lines(inputFile) {
-    for line in lines.flatten() {
-      let round = GameRound::from_str(line.as_str()).unwrap();
+  fn parse(input: &str) -> Result<Self::Parsed<'_>, AocError> {
+    input.lines()
+      .map(|line| GameRound::from_str(line).map_err(|error| error.within(input, line)))
+      .collect()
+  }
+
+  fn part_1(rounds: &Self::Parsed<'_>) -> Result<Answer, AocError> {
+    let maxGreen = 13;
+    let maxRed = 12;
+    let maxBlue = 14;
+
+    let mut possibleGameIdSum = 0;
 
+    for round in rounds {
       if round.is_possible(maxGreen, maxRed, maxBlue) {
         possibleGameIdSum += round.id;
       }
     }
-  }
-
-  println!("Day 2 Part 1: {}", possibleGameIdSum);
 
-}
-
-pub fn part_2(inputFile: Option<&str>) {
-  let inputFile = inputFile.unwrap_or("./inputs/day2part1.txt");
+    Ok(possibleGameIdSum.into())
 
-  let mut powerSum = 0;
+  }
 
-  if let Ok(lines) = file_utils::read_lines(inputFile) {
-    for line in lines.flatten() {
-      let round = GameRound::from_str(line.as_str()).unwrap();
+  fn part_2(rounds: &Self::Parsed<'_>) -> Result<Answer, AocError> {
+    let mut powerSum = 0;
 
+    for round in rounds {
       let minSet = round.get_max_shown();
 
       powerSum += minSet.blue * minSet.red * minSet.green;
     }
+
+    Ok(powerSum.into())
   }
+}
 
-  println!("Day 2 Part 2: {}", powerSum);
+pub fn part_1(inputFile: Option<&str>) -> Result<Answer, AocError> {
+  solution::run_part_1::<Day2>(inputFile)
+}
+
+pub fn part_2(inputFile: Option<&str>) -> Result<Answer, AocError> {
+  solution::run_part_2::<Day2>(inputFile)
 }
 
 #[derive(Debug)]
-struct GameRound {
+pub struct GameRound {
   id: i32,
   sets: Vec<GameSet>
 }
@@ -94,19 +106,14 @@ impl Default for GameRound {
 
 impl FromStr for GameRound {
 
-  type Err = std::num::ParseIntError;
+  type Err = AocError;
 
   fn from_str(gameLine: &str) -> Result<Self, Self::Err> {
 
-    let mut parts = gameLine.split(":");
-
-    let idString = parts.next().expect("Should always be a game id string");
-    let idString = idString.split(" ").last().expect("Should always be an id in the Game Id String");
-    let id: i32 = idString.parse::<i32>().unwrap();
+    let header = delimited(pair(literal("Game"), spaces), unsigned, pair(spaces, literal(":")));
+    let game = pair(header, separated(game_set, literal(";")));
 
-    let resultSets = parts.next().expect("Should always be a result set string");
-    let resultSetStrings = resultSets.split(";");
-    let sets: Vec<GameSet> = resultSetStrings.map(|setString| GameSet::from_str(setString).unwrap()).collect();
+    let (id, sets) = parser::complete(gameLine, game)?;
 
     Ok(GameRound { id, sets } )
   }
@@ -135,31 +142,30 @@ impl Default for GameSet {
 }
 
 impl FromStr for GameSet {
-  type Err = std::num::ParseIntError;
+  type Err = AocError;
 
   fn from_str(setString: &str) -> Result<Self, Self::Err> {
+    parser::complete(setString, game_set)
+  }
+}
 
+/// A `3 blue, 4 red` set of cubes. Colors that aren't shown count as 0.
+fn game_set(input: &str) -> ParseResult<'_, GameSet> {
+  let color = alt(alt(literal("red"), literal("green")), literal("blue"));
+  let cubes = delimited(spaces, pair(terminated(unsigned, spaces), color), spaces);
+
+  map(separated(cubes, literal(",")), |cubes: Vec<(i32, &str)>| {
     let mut gameSet = GameSet::new();
 
-    let sets = setString.split(";");
-    for set in sets {
-      let colors = set.split(',');
-
-      for color in colors {
-        let mut colorParts = color.trim().split(' ');
-        let count = colorParts.next().expect("Should always be a color count");
-        let count = count.parse::<i32>().unwrap();
-        let color = colorParts.next().expect("Should always be a color");
-
-        match color {
-          "red" => gameSet.red = count,
-          "blue" => gameSet.blue = count,
-          "green" => gameSet.green = count,
-          &_ => panic!("Unexpected color name")
-        }
-      }      
+    for (count, colorName) in cubes {
+      match colorName {
+        "red" => gameSet.red = count,
+        "green" => gameSet.green = count,
+        "blue" => gameSet.blue = count,
+        _ => unreachable!("Only red, green and blue are parsed"),
+      }
     }
-    
-    Ok(gameSet)
-  }
-}
\ No newline at end of file
+
+    gameSet
+  })(input)
+}
@@ -1,96 +1,137 @@
 use std::{
-  borrow::Borrow, collections::HashSet, fs, str::FromStr, string::ParseError
+  borrow::Borrow, collections::HashSet, str::FromStr
 };
 
-pub fn part_1(inputFile: Option<&str>) {
-  let inputFile = inputFile.unwrap_or("./inputs/day3part1.txt");
+use crate::repl::{self, Inspect};
+use crate::solution::{self, Answer, Solution};
+use crate::utils::error::AocError;
+use crate::utils::geom::{Direction, Point};
+use crate::utils::grid::Grid;
 
-  let contents = fs::read_to_string(inputFile).expect("File should exist");
+pub struct Day3;
 
-  let schematic = EngineSchematic::from_str(contents.as_str()).unwrap();
+impl Solution for Day3 {
+  const DAY: u8 = 3;
 
-  let possible_starts = schematic.find_start_of_all_possible_parts();
-  
-  let mut part_sum: i32 = 0;
+  type Parsed<'a> = EngineSchematic;
+
+  fn parse(input: &str) -> Result<Self::Parsed<'_>, AocError> {
+    EngineSchematic::from_str(input)
+  }
 
-  for start in possible_starts {
-    if let Some(part) = schematic.get_part_at_location(start) {
-      part_sum += part.parse::<i32>().unwrap();
+  fn part_1(schematic: &Self::Parsed<'_>) -> Result<Answer, AocError> {
+    let possible_starts = schematic.find_start_of_all_possible_parts();
+    
+    let mut part_sum: i32 = 0;
+
+    for start in possible_starts {
+      if let Some(part) = schematic.get_part_at_location(start) {
+        part_sum += part.parse::<i32>().unwrap();
+      }
     }
+
+    Ok(part_sum.into())
   }
 
-  println!("Day 3 Part 1: {}", part_sum);
+  fn part_2(_schematic: &Self::Parsed<'_>) -> Result<Answer, AocError> {
+    Ok(Answer::Unsolved)
+  }
 }
 
-pub fn part_2(inputFile: Option<&str>) {
-  let inputFile = inputFile.unwrap_or("./inputs/day3part1.txt");
-  let contents = fs::read_to_string(inputFile).expect("File should exist");
-  let _schematic = EngineSchematic::from_str(contents.as_str()).unwrap();
+impl Inspect for Day3 {
+  const QUERIES: &'static [&'static str] = &[
+    "cell <x>,<y>        what is at a column and row, and the number it belongs to",
+  ];
 
-}
+  fn query(schematic: &Self::Parsed<'_>, query: &str, args: &str) -> Result<String, String> {
+    if query != "cell" {
+      return Err(repl::unknown(query));
+    }
+
+    let (x, y) = args.split_once(',').ok_or("Usage: cell <x>,<y>")?;
+    let position = Point::new(repl::number(x)?, repl::number(y)?);
+    let component = schematic.get_position(&position).ok_or_else(|| format!("{},{} is outside the schematic", position.x, position.y))?;
+
+    let description = if component.is_symbol() {
+      "a symbol".to_string()
+    } else if let Some(start) = schematic.find_start_of_possible_part(position) {
+      let mut number = String::new();
+      let mut position = start;
+      while let Some(digit) = schematic.get_position(&position).filter(|component| component.is_possible_part()) {
+        number.push(digit.value);
+        position = position.step(Direction::East);
+      }
+
+      match schematic.get_part_at_location(start) {
+        Some(_) => format!("in part number {} starting at {},{}", number, start.x, start.y),
+        None => format!("in {} starting at {},{}, which touches no symbol", number, start.x, start.y),
+      }
+    } else {
+      "empty".to_string()
+    };
 
-#[derive(Default, Debug)]
-struct EngineSchematic {
-  components: Vec<Vec<EngineComponent>>
+    Ok(format!("'{}' at {},{} is {}", component.value, position.x, position.y, description))
+  }
 }
 
-impl EngineSchematic {
+pub fn part_1(inputFile: Option<&str>) -> Result<Answer, AocError> {
+  solution::run_part_1::<Day3>(inputFile)
+}
 
-  fn get_position(self: &Self, position: &Position) -> Option<&EngineComponent> {
+pub fn part_2(inputFile: Option<&str>) -> Result<Answer, AocError> {
+  solution::run_part_2::<Day3>(inputFile)
+}
 
-    let column = self.get_x(position.x as usize)?;
-    let value = column.get(position.y as usize);
+/// The schematic's cells, `x` being the column and `y` the row.
+#[derive(Debug)]
+pub struct EngineSchematic {
+  grid: Grid<EngineComponent>
+}
 
-    return value;
-  }
+impl EngineSchematic {
 
-  fn get_x(self: &Self, x: usize) -> Option<&Vec<EngineComponent>> {
-    self.components.get(x)
-  }
+  fn get_position(self: &Self, position: &Point) -> Option<&EngineComponent> {
+    let (x, y) = position.to_index()?;
 
-  fn get_x_mut(self: &mut Self, x: usize) -> Option<&mut Vec<EngineComponent>> {
-    self.components.get_mut(x)
+    return self.grid.get(x, y);
   }
   
-  fn find_start_of_possible_part(self: &Self, component: &EngineComponent) -> Option<Position> {
-    if !component.is_possible_part() {
+  fn find_start_of_possible_part(self: &Self, position: Point) -> Option<Point> {
+    if !self.get_position(&position)?.is_possible_part() {
       return None;
     }
 
-    if let Some(previousPosition) = component.position.previous() {
+    let previousPosition = position.step(Direction::West);
 
-      let previousComponent = self.get_position(&previousPosition).unwrap();
+    if let Some(previousComponent) = self.get_position(&previousPosition) {
       
       if previousComponent.is_possible_part() {
         // The part continues on
-        return self.find_start_of_possible_part(previousComponent);
+        return self.find_start_of_possible_part(previousPosition);
       } else {
         // We found the start of the part
-        return Some(component.position);
+        return Some(position);
       }
     } else {
       // Has to be the start if it's the start of the row
-      return Some(component.position);
+      return Some(position);
     }
   }
 
-  fn find_start_of_all_possible_parts(self: &Self) -> HashSet<Position> {
+  fn find_start_of_all_possible_parts(self: &Self) -> HashSet<Point> {
 
-    let mut possible_parts_starts: HashSet<Position> = HashSet::new();
+    let mut possible_parts_starts: HashSet<Point> = HashSet::new();
 
-    for col in &self.components {
-      for component in col {        
-        let part_start = self.find_start_of_possible_part(component);
-        if part_start.is_some() {
-          possible_parts_starts.insert(part_start.unwrap());
-        }
+    for ((x, y), _) in self.grid.iter() {
+      if let Some(part_start) = self.find_start_of_possible_part(Point::from((x, y))) {
+        possible_parts_starts.insert(part_start);
       }
     }
 
     return possible_parts_starts;
   }
 
-  fn get_part_at_location(self: &Self, position: Position) -> Option<String> {
+  fn get_part_at_location(self: &Self, position: Point) -> Option<String> {
     
     let mut partString = String::new();
     let mut is_confirmed_part = false;
@@ -112,12 +153,11 @@ impl EngineSchematic {
 
       partString.push(currentComponent.value);
       
-      if self.position_touches_symbol(currentComponent) {
+      if self.position_touches_symbol(currentPosition) {
         is_confirmed_part = true;
       }
       
-      currentPosition = currentPosition.next().unwrap();
-      // println!("Checking pos: {:?}, current string: {}, is part: {}", currentPosition, partString, is_confirmed_part);
+      currentPosition = currentPosition.step(Direction::East);
     }
 
     if !is_confirmed_part {
@@ -127,34 +167,12 @@ impl EngineSchematic {
     return Some(partString);
   }
 
-  fn position_touches_symbol(self: &Self, component: &EngineComponent) -> bool {
-
-    let adjacentPositions = [
-      component.position.fromOffset(-1, -1), // top left
-      component.position.fromOffset(0, -1), // top
-      component.position.fromOffset(1, -1), // top right
-      component.position.fromOffset(-1, 0), // left
-      component.position.fromOffset(1, 0), // right
-      component.position.fromOffset(-1, 1), // bottom left
-      component.position.fromOffset(0, 1),  // bottom
-      component.position.fromOffset(1, 1), // bottom right
-    ];
-
-    for pos in adjacentPositions {
-      let component = self.get_position(&pos);
-
-      if component.is_none() {
-        continue;
-      }
-
-      let component = component.unwrap();
+  fn position_touches_symbol(self: &Self, position: Point) -> bool {
+    let Some((x, y)) = position.to_index() else {
+      return false;
+    };
 
-      if component.is_symbol() {
-        return true;
-      }
-    }
- 
-    return false;
+    self.grid.neighbors_8(x, y).any(|(_, component)| component.is_symbol())
   }
 
   #[allow(dead_code)]
@@ -171,42 +189,24 @@ impl EngineSchematic {
 }
 
 impl FromStr for EngineSchematic {
-  type Err = ParseError;
+  type Err = AocError;
 
   fn from_str(inputString: &str) -> Result<Self, Self::Err> {
-    let mut schematic = Self::default();
-
-    for line in inputString.lines() {
-
-      for x in 0..line.len() {
-        if schematic.components.get(x).is_none() {
-          schematic.components.push(vec![])
-        }
-      }
-
-      for (xPos, char) in line.char_indices() {
-        
-        let yPos = schematic.get_x(xPos).expect("Should be initialized").len();
-
-        let component = EngineComponent {
-          position: Position { x: xPos as isize, y: yPos as isize},
-          value: char
-        };
-
-        schematic.get_x_mut(xPos).expect("Should be initialized").push(component);
-      }
-    }
-
-    Ok(schematic)
+    Ok(EngineSchematic { grid: inputString.parse()? })
   }
 }
 
-#[derive(Default, Debug)]
+#[derive(Debug)]
 struct EngineComponent {
-  position: Position,
   value: char
 }
 
+impl From<char> for EngineComponent {
+  fn from(value: char) -> Self {
+    EngineComponent { value }
+  }
+}
+
 impl EngineComponent {
   fn is_symbol(self: &Self) -> bool {
     match self.value.borrow() {
@@ -237,33 +237,6 @@ impl EngineComponent {
 }
 
 
-#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
-struct Position {
-  x: isize,
-  y: isize
-}
-
-impl Position {
-  fn fromOffset(self: &Self, xOffset: isize, yOffset: isize) -> Position {
-    Position {
-      x: self.x + xOffset,
-      y: self.y + yOffset
-    }
-  }
-
-  fn previous(self: &Self) -> Option<Position> {
-    if self.x <= 0 {
-      return None;
-    }
-
-    Some(Position { x: self.x - 1, y: self.y })
-  }
-
-  fn next(self: &Self) -> Option<Position> {
-    Some(Position { x: self.x + 1, y: self.y })
-  }
-}
-
 #[cfg(test)]
 mod tests {
 
@@ -280,13 +253,13 @@ mod tests {
 
     let schematic = EngineSchematic::from_str(inputString).unwrap();
 
-    assert_eq!(schematic.get_position(&Position { x: 0, y: 0 }).unwrap().value, '0');
-    assert_eq!(schematic.get_position(&Position { x: 0, y: 4 }).unwrap().value, '4');
-    assert_eq!(schematic.get_position(&Position { x: 4, y: 4 }).unwrap().value, '8');
-    assert_eq!(schematic.get_position(&Position { x: 4, y: 0 }).unwrap().value, '4');
-    assert_eq!(schematic.get_position(&Position { x: 2, y: 2 }).unwrap().value, '4');
-    assert_eq!(schematic.components.len(), 5);
-    assert_eq!(schematic.get_x(1).expect("Should be initialized").len(), 5);
+    assert_eq!(schematic.get_position(&Point::new(0, 0)).unwrap().value, '0');
+    assert_eq!(schematic.get_position(&Point::new(0, 4)).unwrap().value, '4');
+    assert_eq!(schematic.get_position(&Point::new(4, 4)).unwrap().value, '8');
+    assert_eq!(schematic.get_position(&Point::new(4, 0)).unwrap().value, '4');
+    assert_eq!(schematic.get_position(&Point::new(2, 2)).unwrap().value, '4');
+    assert_eq!(schematic.grid.width(), 5);
+    assert_eq!(schematic.grid.column(1).count(), 5);
   }
 
   #[test]
@@ -302,11 +275,11 @@ mod tests {
 
     let possiblePartStarts = schematic.find_start_of_all_possible_parts();
 
-    assert!(possiblePartStarts.contains(&Position { x: 1, y: 1 }));
-    assert!(possiblePartStarts.contains(&Position { x: 3, y: 2 }));
-    assert!(possiblePartStarts.contains(&Position { x: 0, y: 3 }));
-    assert!(possiblePartStarts.contains(&Position { x: 1, y: 4 }));
-    assert!(possiblePartStarts.contains(&Position { x: 4, y: 4 }));
+    assert!(possiblePartStarts.contains(&Point::new(1, 1)));
+    assert!(possiblePartStarts.contains(&Point::new(3, 2)));
+    assert!(possiblePartStarts.contains(&Point::new(0, 3)));
+    assert!(possiblePartStarts.contains(&Point::new(1, 4)));
+    assert!(possiblePartStarts.contains(&Point::new(4, 4)));
   }
 
   #[test]
@@ -320,9 +293,9 @@ mod tests {
 
     let schematic = EngineSchematic::from_str(inputString).unwrap();
 
-    assert_eq!(schematic.get_part_at_location(Position { x: 0, y: 0 }), None);
-    assert_eq!(schematic.get_part_at_location(Position { x: 1, y: 1 }), Some("11".to_string()));
-    assert_eq!(schematic.get_part_at_location(Position { x: 3, y: 2 }), None); // 22 does not touch symbol
-    assert_eq!(schematic.get_part_at_location(Position { x: 0, y: 3 }), Some("33333".to_string()));
+    assert_eq!(schematic.get_part_at_location(Point::new(0, 0)), None);
+    assert_eq!(schematic.get_part_at_location(Point::new(1, 1)), Some("11".to_string()));
+    assert_eq!(schematic.get_part_at_location(Point::new(3, 2)), None); // 22 does not touch symbol
+    assert_eq!(schematic.get_part_at_location(Point::new(0, 3)), Some("33333".to_string()));
   }
 }
\ No newline at end of file
@@ -1,10 +1,15 @@
 use std::collections::HashMap;
-use std::fs;
 use std::str::FromStr;
-use std::string::ParseError;
+
+use crate::fuzz::Fuzz;
+use crate::repl::{self, Inspect};
+use crate::solution::{self, Answer, Solution};
+use crate::utils::error::AocError;
+use crate::utils::parser::{self, delimited, literal, pair, separated, spaces, terminated, unsigned};
+use crate::utils::rng::Rng;
 
 #[derive(Default, Debug)]
-struct Card {
+pub struct Card {
   id: i32,
   winningNumbers: Vec<i32>,
   revealedNumbers: Vec<i32>,
@@ -20,35 +25,28 @@ impl Card {
       return match_count as i32;
     }
 
-    let points: i32 = 1 * 2_i32.pow(match_count - 1);
+    let points: i32 = 2_i32.pow(match_count - 1);
     return points;
   }
 }
 
 impl FromStr for Card {
-  type Err = ParseError;
+  type Err = AocError;
 
   fn from_str(s: &str) -> Result<Self, Self::Err> {
     
     let mut new_card = Card::default();
 
-    let mut card_parts = s.split(":");
-
-    let card_id = card_parts.next().unwrap().split(" ").last().unwrap();
-    new_card.id = card_id.parse::<i32>().unwrap();
+    let header = delimited(pair(literal("Card"), spaces), unsigned, pair(spaces, literal(":")));
+    let numbers = || delimited(spaces, separated(unsigned, spaces), spaces);
+    let card = pair(header, pair(terminated(numbers(), literal("|")), numbers()));
 
-    let mut card_parts = card_parts.next().unwrap().split("|");
+    let (id, (winning_numbers, revealed_numbers)) = parser::complete(s, card)?;
 
-    let winning_numbers = card_parts.next().unwrap().trim().split_whitespace();
-    let revealed_numbers = card_parts.next().unwrap().trim().split_whitespace();
-
-    for number in winning_numbers {
-      let number = number.parse::<i32>().unwrap();
-      new_card.winningNumbers.push(number);
-    }
+    new_card.id = id;
+    new_card.winningNumbers = winning_numbers;
 
     for number in revealed_numbers {
-      let number = number.parse::<i32>().unwrap();
       new_card.revealedNumbers.push(number);
 
       if new_card.winningNumbers.contains(&number) {
@@ -56,7 +54,7 @@ impl FromStr for Card {
       }
     }
 
-    if new_card.matchingNumbers.len() > 0 {
+    if !new_card.matchingNumbers.is_empty() {
 
       let endingId = new_card.id + new_card.matchingNumbers.len() as i32;
       let idRange = new_card.id+1..=endingId;
@@ -68,72 +66,200 @@ impl FromStr for Card {
   }
 }
 
-fn get_cards(contents: String) -> HashMap<i32, Card> {
+fn get_cards(contents: &str) -> Result<HashMap<i32, Card>, AocError> {
 
   let mut cards_hashset: HashMap<i32, Card> = HashMap::new();
+  let mut lines = vec![];
 
   for line in contents.lines() {
-    let card = Card::from_str(line).unwrap();
+    let card = Card::from_str(line).map_err(|error| error.within(contents, line))?;
 
+    lines.push((line, card.id));
     cards_hashset.insert(card.id, card);
   }
 
-  return cards_hashset;
+  // Part 2 looks up every card that is won, so a copy past the end of the table has nowhere to go
+  for (line, id) in lines {
+    if let Some(missing) = cards_hashset[&id].resultingCards.iter().find(|won| !cards_hashset.contains_key(won)) {
+      return Err(AocError::new(format!("Card {} wins a copy of card {}, which isn't in the table", id, missing), contents, line));
+    }
+  }
+
+  return Ok(cards_hashset);
 }
 
-fn get_points_sum(cards: HashMap<i32, Card>) -> i32 {
+fn get_points_sum(cards: &HashMap<i32, Card>) -> i32 {
 
   let mut sum = 0;
 
-  for (_id, card) in cards {
+  for card in cards.values() {
     sum += card.getPoints();
   }
 
   return sum;
 }
 
-pub fn part_1() {
-  let inputFile = "./inputs/day4part1.txt";
-  let contents = fs::read_to_string(inputFile).expect("File should exist");
+pub struct Day4;
 
-  let cards = get_cards(contents);
-  let pointSum = get_points_sum(cards);
+impl Solution for Day4 {
+  const DAY: u8 = 4;
 
-  println!("Day 4 Part 1: {}", pointSum);
-}
+  type Parsed<'a> = HashMap<i32, Card>;
 
-pub fn part_2() {
-  let inputFile = "./inputs/day4part1.txt";
-  let contents = fs::read_to_string(inputFile).expect("File should exist");
+  fn parse(input: &str) -> Result<Self::Parsed<'_>, AocError> {
+    get_cards(input)
+  }
+
+  fn part_1(cards: &Self::Parsed<'_>) -> Result<Answer, AocError> {
+    let pointSum = get_points_sum(cards);
 
-  let cards = get_cards(contents);
+    Ok(pointSum.into())
+  }
 
-  let mut total_card_count = cards.len() as i32;
+  fn part_2(cards: &Self::Parsed<'_>) -> Result<Answer, AocError> {
+    let mut total_card_count = cards.len() as i32;
 
-  let mut cards_won = vec![];
-  for (_id, card) in cards.iter() {
-    for resultCard in card.resultingCards.iter() {
-      cards_won.push(resultCard);
+    let mut cards_won = vec![];
+    for (_id, card) in cards.iter() {
+      for resultCard in card.resultingCards.iter() {
+        cards_won.push(resultCard);
+      }
     }
+
+    total_card_count += cards_won.len() as i32;
+
+    while !cards_won.is_empty() {
+
+      let cards_won_clone = cards_won.clone();
+      cards_won.clear();
+
+      for id in cards_won_clone {
+        let card = &cards[id];
+
+        for resultCard in card.resultingCards.iter() {
+          cards_won.push(resultCard);
+        }
+      }
+
+      total_card_count += cards_won.len() as i32;
+    }
+
+    Ok(total_card_count.into())
   }
+}
 
-  total_card_count += cards_won.len() as i32;
+pub fn part_1(inputFile: Option<&str>) -> Result<Answer, AocError> {
+  solution::run_part_1::<Day4>(inputFile)
+}
 
-  while cards_won.len() > 0 {
+pub fn part_2(inputFile: Option<&str>) -> Result<Answer, AocError> {
+  solution::run_part_2::<Day4>(inputFile)
+}
 
-    let cards_won_clone = cards_won.clone();
-    cards_won.clear();
+impl Inspect for Day4 {
+  const QUERIES: &'static [&'static str] = &[
+    "card <n>            a card's numbers, matches, points and the cards it wins copies of",
+  ];
+
+  fn query(cards: &Self::Parsed<'_>, query: &str, args: &str) -> Result<String, String> {
+    match query {
+      "card" => {
+        let id: i32 = repl::number(args)?;
+        let card = cards.get(&id).ok_or_else(|| format!("No card {}", id))?;
+
+        let wins = match (card.resultingCards.first(), card.resultingCards.last()) {
+          (Some(first), Some(last)) => format!("wins copies of cards {}-{}", first, last),
+          _ => "wins no copies".to_string(),
+        };
+
+        Ok(format!(
+          "Card {}: winning {:?}, revealed {:?}\nmatches {:?}, {} point(s), {}",
+          card.id, card.winningNumbers, card.revealedNumbers, card.matchingNumbers, card.getPoints(), wins
+        ))
+      },
+      _ => Err(repl::unknown(query)),
+    }
+  }
+}
 
-    for id in cards_won_clone {
-      let card = cards.get(&id).unwrap();
+impl Fuzz for Day4 {
+  /// Padded like the real input, and never winning copies of cards past the end of the table.
+  fn generate(rng: &mut Rng) -> String {
+    let count = rng.range(1..10);
+    let width = count.to_string().len();
+    let mut cards = String::new();
 
-      for resultCard in card.resultingCards.iter() {
-        cards_won.push(resultCard);
+    for id in 1..=count {
+      let mut numbers: Vec<u64> = (1..100).collect();
+      rng.shuffle(&mut numbers);
+
+      let winning_count = rng.range(1..6) as usize;
+      let revealed_count = rng.range(1..9) as usize;
+      let matches = (rng.range(0..5) as usize).min(winning_count).min(revealed_count).min((count - id) as usize);
+
+      let winning = &numbers[..winning_count];
+      let mut revealed: Vec<u64> = winning[..matches].iter().chain(&numbers[winning_count..winning_count + revealed_count - matches]).copied().collect();
+      rng.shuffle(&mut revealed);
+
+      let pad = |numbers: &[u64]| numbers.iter().map(|number| format!("{:>2}", number)).collect::<Vec<String>>().join(" ");
+      cards.push_str(&format!("Card {:>width$}: {} | {}\n", id, pad(winning), pad(&revealed), width = width));
+    }
+
+    cards
+  }
+
+  /// Counts matches with nested loops and plays part 2 out one card at a time from a queue.
+  fn oracle(input: &str, part: u8) -> Option<Answer> {
+    let mut matches = vec![];
+
+    for (index, line) in input.lines().enumerate() {
+      let (header, numbers) = line.split_once(':')?;
+      if header.strip_prefix("Card")?.trim().parse::<usize>().ok()? != index + 1 {
+        return None;
+      }
+
+      let (winning, revealed) = numbers.split_once('|')?;
+      let parse = |numbers: &str| numbers.split_whitespace().map(|number| number.parse::<u64>().ok()).collect::<Option<Vec<u64>>>();
+      let (winning, revealed) = (parse(winning)?, parse(revealed)?);
+
+      for side in [&winning, &revealed] {
+        if side.iter().enumerate().any(|(index, number)| side[index + 1..].contains(number)) {
+          return None;
+        }
       }
+
+      matches.push(revealed.iter().filter(|number| winning.contains(number)).count());
     }
 
-    total_card_count += cards_won.len() as i32;
+    if matches.iter().enumerate().any(|(index, count)| *count > 0 && index + count >= matches.len()) {
+      return None;
+    }
+
+    if part == 1 {
+      return Some(matches.iter().map(|count| if *count == 0 { 0 } else { 1 << (count - 1) }).sum::<usize>().into());
+    }
+
+    let mut queue: Vec<usize> = (0..matches.len()).collect();
+    let mut total = 0;
+
+    while let Some(card) = queue.pop() {
+      total += 1;
+      queue.extend(card + 1..=card + matches[card]);
+    }
+
+    Some(total.into())
   }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
 
-  println!("Day 4 Part 2: {}", total_card_count);
-}
\ No newline at end of file
+  #[test]
+  fn copies_past_the_table_are_rejected() {
+    let error = Day4::parse("Card 1: 1 2 | 1 5\nCard 2: 3 4 | 3 4").err().unwrap();
+
+    assert_eq!(error.message, "Card 2 wins a copy of card 3, which isn't in the table");
+    assert_eq!((error.line, error.column), (2, 1));
+  }
+}
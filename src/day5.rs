@@ -1,37 +1,46 @@
-use std::string::ParseError;
-use std::sync::{Arc, Mutex};
-use std::thread::{self, JoinHandle};
-use std::{fs, ops::Range};
+use std::ops::Range;
 use std::str::FromStr;
 
+use crate::fuzz::Fuzz;
+use crate::repl::{self, Inspect};
+use crate::solution::{self, Answer, Solution};
+use crate::utils::error::AocError;
+use crate::utils::input::Input;
+use crate::utils::intervals::IntervalSet;
+use crate::utils::parser::{self, line_ending, literal, pair, preceded, separated, spaces, terminated, unsigned, whitespace, Failure, ParseResult};
+use crate::utils::rng::Rng;
+use crate::utils::trace;
+
 #[derive(Debug, Default, Clone)]
-struct Map {
+pub struct Map {
   destination_range: Range<u64>,
   source_range: Range<u64>,
 }
 impl FromStr for Map {
-  type Err = ParseError;
+  type Err = AocError;
 
   fn from_str(s: &str) -> Result<Self, Self::Err> {
-
-      let mut parts = s.split_whitespace();
-
-      let destinationStart = parts.next().unwrap().trim().parse::<u64>().unwrap();
-      let sourceStart = parts.next().unwrap().trim().parse::<u64>().unwrap();
-      let length = parts.next().unwrap().trim().parse::<u64>().unwrap();
-     
-      let destinationEnd = destinationStart + length;
-      let sourceEnd = sourceStart + length;
-
-      let new_map = Map {
-        destination_range: destinationStart..destinationEnd,
-        source_range: sourceStart..sourceEnd,
-      };
-
-      return Ok(new_map);
+    parser::complete(s, map_line)
   }
 }
 
+/// A `destination source length` line. Ranges that would run past `u64::MAX` are rejected at
+/// their length.
+fn map_line(input: &str) -> ParseResult<'_, Map> {
+  let (rest, destinationStart) = terminated(unsigned::<u64>, spaces)(input)?;
+  let (rest, sourceStart) = terminated(unsigned::<u64>, spaces)(rest)?;
+  let (after, length) = unsigned::<u64>(rest)?;
+
+  let (Some(destinationEnd), Some(sourceEnd)) = (destinationStart.checked_add(length), sourceStart.checked_add(length)) else {
+    return Err(Failure::new("a length that keeps both ranges within a u64", rest));
+  };
+
+  Ok((after, Map {
+    destination_range: destinationStart..destinationEnd,
+    source_range: sourceStart..sourceEnd,
+  }))
+}
+
 #[derive(Clone)]
 struct Mapper {
   prevMaps: Vec<Map>, 
@@ -42,8 +51,8 @@ impl Mapper {
 
   fn new(prevMaps: Vec<Map>, nextMaps: Vec<Map>) -> Self {
     Mapper {
-      prevMaps: prevMaps,
-      nextMaps: nextMaps
+      prevMaps,
+      nextMaps
     }
   }
   
@@ -73,43 +82,90 @@ impl Mapper {
   }
 }
 
-pub fn part_1() {
-    let inputFile = "./inputs/day5part1.txt";
-    let contents = fs::read_to_string(inputFile).expect("File should exist");
-
-    let mut parts = contents.split("\r\n\r\n");
-
-    let seed_string = parts.next().unwrap().split(':').last().unwrap().trim();
-    let seed_to_soil = parts.next().unwrap().split(':').last().unwrap().trim();
-    let soil_to_fertilizer = parts.next().unwrap().split(':').last().unwrap().trim();
-    let fertilizer_to_water = parts.next().unwrap().split(':').last().unwrap().trim();
-    let water_to_light = parts.next().unwrap().split(':').last().unwrap().trim();
-    let light_to_temperature = parts.next().unwrap().split(':').last().unwrap().trim();
-    let temperature_to_humidity = parts.next().unwrap().split(':').last().unwrap().trim();
-    let humidity_to_location = parts.next().unwrap().split(':').last().unwrap().trim();
-
-    let seeds: Vec<u64> = seed_string.split_whitespace().map(|s| s.parse::<u64>().unwrap()).collect();
-
-    let seed_to_soil_maps: Vec<Map> = seed_to_soil.lines().map(|str| Map::from_str(str).unwrap() ).collect();
-    let soil_to_fertilizer_maps: Vec<Map> = soil_to_fertilizer.lines().map(|str| Map::from_str(str).unwrap() ).collect();
-    let fertilizer_to_water_maps: Vec<Map> = fertilizer_to_water.lines().map(|str| Map::from_str(str).unwrap() ).collect();
-    let water_to_light_maps: Vec<Map> = water_to_light.lines().map(|str| Map::from_str(str).unwrap() ).collect();
-    let light_to_temperature_maps: Vec<Map> = light_to_temperature.lines().map(|str| Map::from_str(str).unwrap() ).collect();
-    let temperature_to_humidity_maps: Vec<Map> = temperature_to_humidity.lines().map(|str| Map::from_str(str).unwrap() ).collect();
-    let humidity_to_location_maps: Vec<Map> = humidity_to_location.lines().map(|str| Map::from_str(str).unwrap() ).collect();
-
-    let seed_mapper = Mapper::new(vec![], seed_to_soil_maps.clone());
-    let soil_mapper = Mapper::new(seed_to_soil_maps.clone(), soil_to_fertilizer_maps.clone());
-    let fertilizer_mapper = Mapper::new( soil_to_fertilizer_maps.clone(), fertilizer_to_water_maps.clone());
-    let water_mapper = Mapper::new(fertilizer_to_water_maps.clone(), water_to_light_maps.clone());
-    let light_mapper = Mapper::new(water_to_light_maps.clone(), light_to_temperature_maps.clone());
-    let temperature_mapper = Mapper::new(light_to_temperature_maps.clone(), temperature_to_humidity_maps.clone());
-    let humidity_mapper = Mapper::new(temperature_to_humidity_maps.clone(), humidity_to_location_maps.clone());
-    let _location_mapper = Mapper::new(humidity_to_location_maps.clone(), vec![]);
+#[derive(Debug, Default)]
+pub struct Almanac {
+  seeds: Vec<u64>,
+  seed_to_soil_maps: Vec<Map>,
+  soil_to_fertilizer_maps: Vec<Map>,
+  fertilizer_to_water_maps: Vec<Map>,
+  water_to_light_maps: Vec<Map>,
+  light_to_temperature_maps: Vec<Map>,
+  temperature_to_humidity_maps: Vec<Map>,
+  humidity_to_location_maps: Vec<Map>,
+}
+
+impl FromStr for Almanac {
+  type Err = AocError;
+
+  fn from_str(raw: &str) -> Result<Self, Self::Err> {
+    let input = Input::new(raw);
+    let contents = input.text();
+    let mut sections = input.sections();
+
+    let mut next_section = |name: &str| -> Result<&str, AocError> {
+      sections.next()
+        .map(|section| section.text)
+        .ok_or_else(|| AocError::end_of_input(format!("Expected the {} section", name), contents))
+    };
+
+    let sections_span = trace::span("split sections");
+    let seed_string = next_section("seeds")?;
+    let map_strings = MAP_NAMES.iter()
+      .map(|name| next_section(&format!("{} map", name)))
+      .collect::<Result<Vec<&str>, AocError>>()?;
+    drop(sections_span);
+
+    let seeds = preceded(pair(literal("seeds:"), spaces), separated(unsigned, spaces));
+    let seeds = parser::complete(seed_string, seeds).map_err(|error| error.within(contents, seed_string))?;
+
+    let _span = trace::span("parse maps");
+
+    let mut maps = MAP_NAMES.iter().zip(map_strings).map(|(name, section)| {
+      let header = pair(literal(name), pair(literal(" map:"), whitespace));
+      parser::complete(section, preceded(header, separated(map_line, line_ending))).map_err(|error| error.within(contents, section))
+    });
+    let mut next_maps = || maps.next().expect("There is a section for every map name");
+
+    Ok(Almanac {
+      seeds,
+      seed_to_soil_maps: next_maps()?,
+      soil_to_fertilizer_maps: next_maps()?,
+      fertilizer_to_water_maps: next_maps()?,
+      water_to_light_maps: next_maps()?,
+      light_to_temperature_maps: next_maps()?,
+      temperature_to_humidity_maps: next_maps()?,
+      humidity_to_location_maps: next_maps()?,
+    })
+  }
+}
+
+pub struct Day5;
+
+impl Solution for Day5 {
+  const DAY: u8 = 5;
 
+  type Parsed<'a> = Almanac;
+
+  fn parse(input: &str) -> Result<Self::Parsed<'_>, AocError> {
+    Almanac::from_str(input)
+  }
+
+  fn part_1(almanac: &Self::Parsed<'_>) -> Result<Answer, AocError> {
+    let build_span = trace::span("build mappers");
+    let seed_mapper = Mapper::new(vec![], almanac.seed_to_soil_maps.clone());
+    let soil_mapper = Mapper::new(almanac.seed_to_soil_maps.clone(), almanac.soil_to_fertilizer_maps.clone());
+    let fertilizer_mapper = Mapper::new(almanac.soil_to_fertilizer_maps.clone(), almanac.fertilizer_to_water_maps.clone());
+    let water_mapper = Mapper::new(almanac.fertilizer_to_water_maps.clone(), almanac.water_to_light_maps.clone());
+    let light_mapper = Mapper::new(almanac.water_to_light_maps.clone(), almanac.light_to_temperature_maps.clone());
+    let temperature_mapper = Mapper::new(almanac.light_to_temperature_maps.clone(), almanac.temperature_to_humidity_maps.clone());
+    let humidity_mapper = Mapper::new(almanac.temperature_to_humidity_maps.clone(), almanac.humidity_to_location_maps.clone());
+    let _location_mapper = Mapper::new(almanac.humidity_to_location_maps.clone(), vec![]);
+    drop(build_span);
+
+    let _span = trace::span("map seeds");
     let mut min_seed_location: (u64, u64) = (u64::MAX, u64::MAX);
 
-    for seed in seeds {
+    for seed in almanac.seeds.iter().copied() {
       let soil = seed_mapper.next(seed);
       let fertilizer = soil_mapper.next(soil);
       let water = fertilizer_mapper.next(fertilizer);
@@ -123,108 +179,190 @@ pub fn part_1() {
       }      
     }
 
-    println!("Day 5 Part 1: {}", min_seed_location.1);
+    Ok(min_seed_location.1.into())
+  }
 
-}
+  fn part_2(almanac: &Self::Parsed<'_>) -> Result<Answer, AocError> {
+    let seeds = &almanac.seeds;
 
-pub fn part_2() {
-  let inputFile = "./inputs/day5part1.txt";
-  
-  let contents = fs::read_to_string(inputFile).expect("File should exist");
-
-  let mut parts = contents.split("\r\n\r\n");
-
-  let seed_string = parts.next().unwrap().split(':').last().unwrap().trim();
-  let seed_to_soil = parts.next().unwrap().split(':').last().unwrap().trim();
-  let soil_to_fertilizer = parts.next().unwrap().split(':').last().unwrap().trim();
-  let fertilizer_to_water = parts.next().unwrap().split(':').last().unwrap().trim();
-  let water_to_light = parts.next().unwrap().split(':').last().unwrap().trim();
-  let light_to_temperature = parts.next().unwrap().split(':').last().unwrap().trim();
-  let temperature_to_humidity = parts.next().unwrap().split(':').last().unwrap().trim();
-  let humidity_to_location = parts.next().unwrap().split(':').last().unwrap().trim();
-  
-  let seeds: Vec<u64> = seed_string.split_whitespace().map(|s| s.parse::<u64>().unwrap()).collect();
+    let mut values: IntervalSet<u64> = seeds.chunks_exact(2).map(|pair| pair[0]..pair[0] + pair[1]).collect();
+
+    // Cut the ranges wherever a map starts or ends, so each piece moves by a single offset
+    for maps in almanac.maps() {
+      let _span = trace::span("map ranges");
+      let boundaries = maps.iter().flat_map(|map| [map.source_range.start, map.source_range.end]);
 
-  let mut seed_ranges = Vec::<Range<u64>>::new();
-  for (seed_idx, seed) in seeds.iter().enumerate() {
-    if seed_idx % 2 == 0 {
-      seed_ranges.push(*seed..(*seed+*seeds.get(seed_idx + 1).unwrap()))
+      values = values.split_by(boundaries).into_iter().map(|piece| {
+        match maps.iter().find(|map| map.source_range.contains(&piece.start)) {
+          Some(map) => {
+            let start = piece.start - map.source_range.start + map.destination_range.start;
+            start..start + (piece.end - piece.start)
+          },
+          None => piece,
+        }
+      }).collect();
     }
+
+    Ok(values.min().expect("There is at least one seed range").into())
   }
+}
 
-  let seed_to_soil_maps: Vec<Map> = seed_to_soil.lines().map(|str| Map::from_str(str).unwrap() ).collect();
-  let soil_to_fertilizer_maps: Vec<Map> = soil_to_fertilizer.lines().map(|str| Map::from_str(str).unwrap() ).collect();
-  let fertilizer_to_water_maps: Vec<Map> = fertilizer_to_water.lines().map(|str| Map::from_str(str).unwrap() ).collect();
-  let water_to_light_maps: Vec<Map> = water_to_light.lines().map(|str| Map::from_str(str).unwrap() ).collect();
-  let light_to_temperature_maps: Vec<Map> = light_to_temperature.lines().map(|str| Map::from_str(str).unwrap() ).collect();
-  let temperature_to_humidity_maps: Vec<Map> = temperature_to_humidity.lines().map(|str| Map::from_str(str).unwrap() ).collect();
-  let humidity_to_location_maps: Vec<Map> = humidity_to_location.lines().map(|str| Map::from_str(str).unwrap() ).collect();
+pub fn part_1(inputFile: Option<&str>) -> Result<Answer, AocError> {
+  solution::run_part_1::<Day5>(inputFile)
+}
 
-  let overall_min_seed_location = Arc::new(Mutex::new((u64::MAX, u64::MAX)));
+pub fn part_2(inputFile: Option<&str>) -> Result<Answer, AocError> {
+  solution::run_part_2::<Day5>(inputFile)
+}
 
-  let mut handles: Vec<JoinHandle<()>> = vec![];
-  let mut maxLocationChecked = 0;
-  
-for _ in 0..8 {
-
-    let nextLocationRange = maxLocationChecked..maxLocationChecked+100_000_000;
-    maxLocationChecked += 100_000_000;
-
-    let handle = thread::spawn({
-
-      let overall_min_seed_location = Arc::clone(&overall_min_seed_location);
-      let locationRange = nextLocationRange.clone();
-      let seed_ranges = seed_ranges.clone();
-
-      let soil_mapper = Mapper::new(seed_to_soil_maps.clone(), soil_to_fertilizer_maps.clone());
-      let fertilizer_mapper = Mapper::new(soil_to_fertilizer_maps.clone(), fertilizer_to_water_maps.clone());
-      let water_mapper = Mapper::new(fertilizer_to_water_maps.clone(), water_to_light_maps.clone());
-      let light_mapper = Mapper::new(water_to_light_maps.clone(), light_to_temperature_maps.clone());
-      let temperature_mapper = Mapper::new(light_to_temperature_maps.clone(), temperature_to_humidity_maps.clone());
-      let humidity_mapper = Mapper::new(temperature_to_humidity_maps.clone(), humidity_to_location_maps.clone());
-      let _location_mapper = Mapper::new(humidity_to_location_maps.clone(), vec![]);      
-
-      move || {
-      
-        for location in locationRange {
-          
-          let humidity = _location_mapper.prev(location);
-          let temperature = humidity_mapper.prev(humidity);
-          let light = temperature_mapper.prev(temperature);
-          let water = light_mapper.prev(light);
-          let fertilizer = water_mapper.prev(water);
-          let soil = fertilizer_mapper.prev(fertilizer);
-          let seed = soil_mapper.prev(soil);
-
-          let mut seedFound = false;
-
-          for seed_range in seed_ranges.iter() {
-            if seed_range.contains(&seed) {
-              seedFound = true;
-              break;
-            }
-          }
-
-          if seedFound == false {
-            continue;
-          }
-
-          let mut min_seed_location = overall_min_seed_location.lock().unwrap();
-          if location < min_seed_location.1 {
-            *min_seed_location = (seed, location);
-          }      
+impl Almanac {
+  /// The maps in the order a seed goes through them.
+  fn maps(self: &Self) -> [&Vec<Map>; 7] {
+    [
+      &self.seed_to_soil_maps, &self.soil_to_fertilizer_maps, &self.fertilizer_to_water_maps, &self.water_to_light_maps,
+      &self.light_to_temperature_maps, &self.temperature_to_humidity_maps, &self.humidity_to_location_maps,
+    ]
+  }
+}
+
+const CATEGORIES: [&str; 8] = ["seed", "soil", "fertilizer", "water", "light", "temperature", "humidity", "location"];
+
+impl Inspect for Day5 {
+  const QUERIES: &'static [&'static str] = &[
+    "trace seed <n>      follow a seed through every map to its location",
+    "trace location <n>  follow a location back through every map to its seed",
+  ];
+
+  fn query(almanac: &Self::Parsed<'_>, query: &str, args: &str) -> Result<String, String> {
+    let usage = "Usage: trace seed <n> or trace location <n>";
+
+    if query != "trace" {
+      return Err(repl::unknown(query));
+    }
+
+    let (from, value) = args.split_once(' ').ok_or(usage)?;
+    let mut value: u64 = repl::number(value)?;
+    let mut steps = vec![value];
+
+    match from {
+      "seed" => {
+        for maps in almanac.maps() {
+          value = Mapper::new(vec![], maps.clone()).next(value);
+          steps.push(value);
         }
+      },
+      "location" => {
+        for maps in almanac.maps().into_iter().rev() {
+          value = Mapper::new(maps.clone(), vec![]).prev(value);
+          steps.insert(0, value);
+        }
+      },
+      _ => return Err(usage.to_string()),
+    }
+
+    let path: Vec<String> = CATEGORIES.iter().zip(steps).map(|(category, value)| format!("{} {}", category, value)).collect();
+
+    Ok(path.join(" -> "))
+  }
+}
+
+const MAP_NAMES: [&str; 7] = [
+  "seed-to-soil", "soil-to-fertilizer", "fertilizer-to-water", "water-to-light",
+  "light-to-temperature", "temperature-to-humidity", "humidity-to-location",
+];
+
+impl Fuzz for Day5 {
+  /// Small almanacs where seeds often land on the first, last or just past the last value of a range.
+  fn generate(rng: &mut Rng) -> String {
+    // An even count, so part 2 can read them as start and length pairs
+    let seeds: Vec<String> = (0..rng.range(1..5) * 2).map(|_| rng.range(0..100).to_string()).collect();
+    let mut almanac = format!("seeds: {}\n", seeds.join(" "));
+
+    for name in MAP_NAMES {
+      let mut maps = vec![];
+      let mut start = rng.range(0..20);
+
+      for _ in 0..rng.range(0..5) {
+        let length = rng.range(1..20);
+        maps.push(format!("{} {} {}\n", rng.range(0..100), start, length));
+        start += length + rng.range(0..10);
       }
-    });
 
-    handles.push(handle);
+      rng.shuffle(&mut maps);
+      almanac.push_str(&format!("\n{} map:\n{}", name, maps.concat()));
+    }
 
+    almanac
   }
-  
-  for handle in handles {
-    handle.join().unwrap();
+
+  /// Walks each seed through every map in turn, checking each range by hand. Part 2 expands the
+  /// seed ranges into every seed they hold.
+  fn oracle(input: &str, part: u8) -> Option<Answer> {
+    let mut sections = input.split("\n\n");
+
+    let seeds = sections.next()?.trim().strip_prefix("seeds:")?
+      .split_whitespace()
+      .map(|seed| seed.parse::<u64>().ok())
+      .collect::<Option<Vec<u64>>>()?;
+
+    let seeds: Vec<u64> = match part {
+      1 => seeds,
+      _ if seeds.len() % 2 == 0 => seeds.chunks(2).flat_map(|pair| pair[0]..pair[0] + pair[1]).collect(),
+      _ => return None,
+    };
+
+    let mut maps: Vec<Vec<(u64, u64, u64)>> = vec![];
+
+    for section in sections {
+      let mut lines = section.trim().lines();
+      lines.next()?.strip_suffix(" map:")?;
+
+      let ranges = lines.map(|line| {
+        let numbers = line.split_whitespace().map(|number| number.parse::<u64>().ok()).collect::<Option<Vec<u64>>>()?;
+        match numbers[..] {
+          [destination, source, length] => Some((destination, source, length)),
+          _ => None,
+        }
+      }).collect::<Option<Vec<(u64, u64, u64)>>>()?;
+
+      let overlapping = ranges.iter().enumerate().any(|(index, (_, source, length))| {
+        ranges[index + 1..].iter().any(|(_, other, other_length)| source < &(other + other_length) && other < &(source + length))
+      });
+
+      if overlapping {
+        return None;
+      }
+
+      maps.push(ranges);
+    }
+
+    if maps.len() != MAP_NAMES.len() {
+      return None;
+    }
+
+    let locations = seeds.iter().map(|seed| {
+      maps.iter().fold(*seed, |value, ranges| {
+        ranges.iter()
+          .find(|(_, source, length)| *source <= value && value < source + length)
+          .map(|(destination, source, _)| destination + (value - source))
+          .unwrap_or(value)
+      })
+    });
+
+    locations.min().map(Answer::from)
   }
+}
 
-  println!("Day 5 Part 2: {:?}", overall_min_seed_location.lock().unwrap());
+#[cfg(test)]
+mod tests {
+  use super::*;
 
+  #[test]
+  fn ranges_past_the_end_of_u64_are_rejected() {
+    assert_eq!(Map::from_str("50 98 2").unwrap().source_range, 98..100);
+
+    let error = Map::from_str("0 18446744073709551610 10").unwrap_err();
+    assert_eq!(error.message, "Expected a length that keeps both ranges within a u64, found '10'");
+    assert_eq!((error.line, error.column), (1, 24));
+  }
 }
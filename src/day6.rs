@@ -1,4 +1,7 @@
-use std::fs;
+use crate::solution::{self, Answer, Solution};
+use crate::utils::error::AocError;
+use crate::utils::parser::{self, line_ending, literal, pair, recognize, separated, spaces, terminated, unsigned};
+use crate::utils::scan;
 
 fn get_winning_results(race: (&u64, &u64)) -> Vec<u64> {
   let raceTimeMs = race.0;
@@ -17,63 +20,96 @@ fn get_winning_results(race: (&u64, &u64)) -> Vec<u64> {
   return winningResults;
 }
 
-pub fn part_1(inputFile: Option<&str>) {
-  let inputFile = inputFile.unwrap_or("./inputs/day6part1.txt");
-  
-  let contents = fs::read_to_string(inputFile).expect("File should exist");
-  let mut contents = contents.lines();
+/// The races read column by column, and the single race part 2 gets by joining each row's digits.
+pub struct RaceSheet {
+  races: Vec<(u64, u64)>,
+  joined: (u64, u64)
+}
 
-  let times: Vec<u64> = contents.next().unwrap().split_whitespace().skip(1).map(|t| t.parse().unwrap()).collect();
-  let distances: Vec<u64> = contents.next().unwrap().split_whitespace().skip(1).map(|d| d.parse().unwrap()).collect();
-  
-  let races: Vec<(&u64, &u64)> = times.iter().zip(distances.iter()).collect();
+pub struct Day6;
 
-  let mut resultProduct = 1;
+impl Solution for Day6 {
+  const DAY: u8 = 6;
 
-  for race in races {
-    resultProduct *= get_winning_results(race).len();
-  }
+  type Parsed<'a> = RaceSheet;
 
-  println!("Day 6 Part 1: {}", resultProduct);
-}
+  fn parse(input: &str) -> Result<Self::Parsed<'_>, AocError> {
+    let row = |name: &'static str| pair(terminated(literal(name), spaces), separated(recognize(unsigned::<u64>), spaces));
+    let sheet = pair(terminated(row("Time:"), line_ending), row("Distance:"));
 
-pub fn part_2(inputFile: Option<&str>) {
-  let inputFile = inputFile.unwrap_or("./inputs/day6part1.txt");
+    let ((timeLabel, times), (distanceLabel, distances)) = parser::complete(input, sheet)?;
 
-  let contents = fs::read_to_string(inputFile).expect("File should exist");
-  let mut contents = contents.lines();
+    for (label, row) in [(timeLabel, &times), (distanceLabel, &distances)] {
+      if row.is_empty() {
+        return Err(AocError::new(format!("Expected at least one number after '{}'", label.trim_end()), input, &label[label.len()..]));
+      }
+    }
 
-  let times: Vec<&str> = contents.next().unwrap().split_whitespace().skip(1).collect();
-  let times = times.concat().parse::<u64>().unwrap();
-  let distances: Vec<&str> = contents.next().unwrap().split_whitespace().skip(1).collect();
-  let distances = distances.concat().parse::<u64>().unwrap();
-  
-  let race = (&times, &distances);
+    if times.len() != distances.len() {
+      let (extra, message) = match times.len() > distances.len() {
+        true => (times[distances.len()], "This time has no distance below it"),
+        false => (distances[times.len()], "This distance has no time above it"),
+      };
+      return Err(AocError::new(message, input, extra));
+    }
 
-  let result = get_winning_results(race).len();
-  
-  println!("Day 6 Part 2: {}", result);
-}
+    let value = |number: &str| scan::parse_unsigned(number.as_bytes())
+      .ok_or_else(|| AocError::new("Expected a number that fits in a u64", input, number));
+    let joined = |row: &[&str]| scan::parse_unsigned(row.concat().as_bytes())
+      .ok_or_else(|| AocError::new("The row joined into one number doesn't fit in a u64", input, row[0]));
 
-#[cfg(test)]
-mod tests {
-  use super::*;
+    let races = times.iter().zip(&distances)
+      .map(|(time, distance)| Ok((value(time)?, value(distance)?)))
+      .collect::<Result<Vec<(u64, u64)>, AocError>>()?;
 
-  #[test]
-  fn part_1_using_test_file() {
-    let inputFile = "./inputs/day6test.txt";
+    Ok(RaceSheet { races, joined: (joined(&times)?, joined(&distances)?) })
+  }
+
+  fn part_1(sheet: &Self::Parsed<'_>) -> Result<Answer, AocError> {
+    let mut resultProduct = 1;
+
+    for (time, distance) in &sheet.races {
+      resultProduct *= get_winning_results((time, distance)).len();
+    }
+
+    Ok(resultProduct.into())
+  }
 
-    part_1(Some(inputFile));
+  fn part_2(sheet: &Self::Parsed<'_>) -> Result<Answer, AocError> {
+    let (times, distances) = sheet.joined;
+    
+    let race = (&times, &distances);
 
-    // should print 288
+    let result = get_winning_results(race).len();
+    
+    Ok(result.into())
   }
+}
 
-  #[test]
-  fn part_2_using_test_file() {
-    let inputFile = "./inputs/day6test.txt";
+pub fn part_1(inputFile: Option<&str>) -> Result<Answer, AocError> {
+  solution::run_part_1::<Day6>(inputFile)
+}
 
-    part_2(Some(inputFile));
+pub fn part_2(inputFile: Option<&str>) -> Result<Answer, AocError> {
+  solution::run_part_2::<Day6>(inputFile)
+}
 
-    // should print 71503
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn rows_must_pair_up() {
+    let error = Day6::parse("Time: 7 15\nDistance: 9").err().unwrap();
+    assert_eq!(error.message, "This time has no distance below it");
+    assert_eq!((error.line, error.column), (1, 9));
+
+    let error = Day6::parse("Time:\nDistance:").err().unwrap();
+    assert_eq!(error.message, "Expected at least one number after 'Time:'");
+    assert_eq!((error.line, error.column), (1, 6));
+
+    let error = Day6::parse("Time: 9999999999 9999999999\nDistance: 1 2").err().unwrap();
+    assert_eq!(error.message, "The row joined into one number doesn't fit in a u64");
+    assert_eq!((error.line, error.column), (1, 7));
   }
-}
\ No newline at end of file
+}
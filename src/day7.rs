@@ -1,30 +1,44 @@
-use std::{cmp, collections::HashMap, fs, str::FromStr, string::ParseError};
+use std::{cmp, collections::HashMap, str::FromStr};
+
+use crate::fuzz::Fuzz;
+use crate::solution::{self, Answer, Solution};
+use crate::utils::error::AocError;
+use crate::utils::parser::{self, pair, spaces, take_while1, terminated, unsigned};
+use crate::utils::rng::Rng;
+
 #[derive(Debug)]
 struct Card {
-  label: String,
+  label: char,
   strength: i32
 }
 
-impl FromStr for Card {
-  type Err = ParseError;
-
-  fn from_str(s: &str) -> Result<Self, Self::Err> {
+impl Card {
+  /// Parses a single card label. With jokers wild `J` is the weakest card, otherwise it is a
+  /// jack between `T` and `Q`.
+  fn parse<const WILDCARD_ACTIVE: bool>(s: &str) -> Result<Self, AocError> {
       
-      let card: Card = match s {
-        "A" => Card { label: s.to_string(), strength: 13 },
-        "K" => Card { label: s.to_string(), strength: 12 },
-        "Q" => Card { label: s.to_string(), strength: 11 },
-        "T" => Card { label: s.to_string(), strength: 10 },
-        "9" => Card { label: s.to_string(), strength: 9 },
-        "8" => Card { label: s.to_string(), strength: 8 },
-        "7" => Card { label: s.to_string(), strength: 7 },
-        "6" => Card { label: s.to_string(), strength: 6 },
-        "5" => Card { label: s.to_string(), strength: 5 },
-        "4" => Card { label: s.to_string(), strength: 4 },
-        "3" => Card { label: s.to_string(), strength: 3 },
-        "2" => Card { label: s.to_string(), strength: 2 },
-        "J" => Card { label: s.to_string(), strength: 1 },
-        _ => panic!("Unhandled card")
+      let mut chars = s.chars();
+      let label = match (chars.next(), chars.next()) {
+        (Some(label), None) => label,
+        _ => return Err(AocError::new(format!("Unhandled card '{}'", s), s, s)),
+      };
+
+      let card: Card = match label {
+        'A' => Card { label: 'A', strength: 14 },
+        'K' => Card { label: 'K', strength: 13 },
+        'Q' => Card { label: 'Q', strength: 12 },
+        'T' => Card { label: 'T', strength: 10 },
+        '9' => Card { label: '9', strength: 9 },
+        '8' => Card { label: '8', strength: 8 },
+        '7' => Card { label: '7', strength: 7 },
+        '6' => Card { label: '6', strength: 6 },
+        '5' => Card { label: '5', strength: 5 },
+        '4' => Card { label: '4', strength: 4 },
+        '3' => Card { label: '3', strength: 3 },
+        '2' => Card { label: '2', strength: 2 },
+        'J' if WILDCARD_ACTIVE => Card { label: 'J', strength: 1 },
+        'J' => Card { label: 'J', strength: 11 },
+        _ => return Err(AocError::new(format!("Unhandled card '{}'", s), s, s))
       };
 
       Ok(card)
@@ -34,7 +48,7 @@ impl FromStr for Card {
 #[derive(Debug)]
 #[allow(dead_code)]
 struct HandType {
-  label: String,
+  label: &'static str,
   strength: i32
 }
 
@@ -55,18 +69,7 @@ impl<const WILDCARD_ACTIVE: bool> PartialEq for Hand<WILDCARD_ACTIVE> {
 
 impl<const WILDCARD_ACTIVE: bool> PartialOrd for Hand<WILDCARD_ACTIVE> {
   fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
-
-    if self.handType.strength == other.handType.strength {
-      for i in 0..self.cards.len() {
-        if self.cards[i].strength == other.cards[i].strength {
-          continue;
-        }
-
-        return self.cards[i].strength.partial_cmp(&other.cards[i].strength); 
-      }
-    }
-
-    self.handType.strength.partial_cmp(&other.handType.strength)
+    Some(self.cmp(other))
   }
 }
 
@@ -88,7 +91,7 @@ impl<const WILDCARD_ACTIVE: bool> Ord for Hand<WILDCARD_ACTIVE> {
 }
 
 impl Hand<false> {
-  fn get_hand_type(cards: &Vec<Card>) -> HandType {
+  fn get_hand_type(cards: &[Card]) -> HandType {
     
     let mut cardHash = HashMap::new();
 
@@ -98,28 +101,28 @@ impl Hand<false> {
 
     if *cardHash.values().max().unwrap() == 5 {
       return HandType {
-        label: "Five of a kind".into(),
+        label: "Five of a kind",
         strength: 7
       };
     }
     
     if *cardHash.values().max().unwrap() == 4 {
       return HandType {
-        label: "Four of a kind".into(),
+        label: "Four of a kind",
         strength: 6
       };
     }
 
-    if *cardHash.values().max().unwrap() == 3 && cardHash.values().find(|v| **v == 2).is_some() {
+    if *cardHash.values().max().unwrap() == 3 && cardHash.values().any(|v| *v == 2) {
       return HandType {
-        label: "Full house".into(),
+        label: "Full house",
         strength: 5
       };
     }
 
     if *cardHash.values().max().unwrap() == 3 {
       return HandType {
-        label: "Three of a kind".into(),
+        label: "Three of a kind",
         strength: 4
       };
     }
@@ -134,7 +137,7 @@ impl Hand<false> {
 
       if pairs_found == 2 {
         return HandType {
-          label: "Two pair".into(),
+          label: "Two pair",
           strength: 3
         };
       }
@@ -142,20 +145,20 @@ impl Hand<false> {
 
     if cardHash.values().max() == Some(&2) {
       return HandType {
-        label: "One pair".into(),
+        label: "One pair",
         strength: 2
       };
     }
 
     HandType {
-      label: "High card".into(),
+      label: "High card",
       strength: 1
     }
   }
 }
 
 impl Hand<true> {
-  fn get_hand_type(cards: &Vec<Card>) -> HandType {
+  fn get_hand_type(cards: &[Card]) -> HandType {
     
     let mut cardHash = HashMap::new();
 
@@ -163,7 +166,7 @@ impl Hand<true> {
 
     for card in cards.iter() {
       
-      if card.label == "J" {
+      if card.label == 'J' {
         jokerCount += 1;
         continue;
       } 
@@ -173,14 +176,14 @@ impl Hand<true> {
 
     if jokerCount >= 5 || *cardHash.values().max().unwrap() >= (5 - jokerCount) {
       return HandType {
-        label: "Five of a kind".into(),
+        label: "Five of a kind",
         strength: 7
       };
     }
     
     if *cardHash.values().max().unwrap() >= (4 - jokerCount) {
       return HandType {
-        label: "Four of a kind".into(),
+        label: "Four of a kind",
         strength: 6
       };
     }
@@ -196,20 +199,20 @@ impl Hand<true> {
 
       if pairs_found == 2 {
         return HandType {
-          label: "Full house".into(),
+          label: "Full house",
           strength: 5
         };
       }
-    } else if *cardHash.values().max().unwrap() == 3 && cardHash.values().find(|v| **v == 2).is_some() {
+    } else if *cardHash.values().max().unwrap() == 3 && cardHash.values().any(|v| *v == 2) {
       return HandType {
-        label: "Full house".into(),
+        label: "Full house",
         strength: 5
       };
     }
 
     if *cardHash.values().max().unwrap() >= (3 - jokerCount) {
       return HandType {
-        label: "Three of a kind".into(),
+        label: "Three of a kind",
         strength: 4
       };
     }
@@ -225,7 +228,7 @@ impl Hand<true> {
 
       if pairs_found == 2 {
         return HandType {
-          label: "Two pair".into(),
+          label: "Two pair",
           strength: 3
         };
       }
@@ -233,28 +236,35 @@ impl Hand<true> {
 
     if *cardHash.values().max().unwrap() >= (2 - jokerCount) {
       return HandType {
-        label: "One pair".into(),
+        label: "One pair",
         strength: 2
       };
     }
 
     HandType {
-      label: "High card".into(),
+      label: "High card",
       strength: 1
     }
   }
 }
 
 impl<const WILDCARD_ACTIVE: bool> FromStr for Hand<WILDCARD_ACTIVE> {
-  type Err = ParseError;
+  type Err = AocError;
 
   fn from_str(s: &str) -> Result<Self, Self::Err> {
-    let mut parts = s.split_whitespace();
+    let hand = take_while1("a hand", |c| !c.is_whitespace());
+    let (hand, bid) = parser::complete(s, pair(terminated(hand, spaces), unsigned::<i32>))?;
 
-    let hand = parts.next().unwrap();
-    let bid: i32 = parts.next().unwrap().parse().unwrap();
+    if hand.chars().count() != 5 {
+      return Err(AocError::new("A hand must have exactly 5 cards", s, hand));
+    }
 
-    let cards: Vec<Card> = hand.chars().map(|c| Card::from_str(&c.to_string()).unwrap()).collect();
+    let cards = hand.char_indices()
+      .map(|(index, c)| {
+        let label = &hand[index..index + c.len_utf8()];
+        Card::parse::<WILDCARD_ACTIVE>(label).map_err(|error| error.within(s, label))
+      })
+      .collect::<Result<Vec<Card>, AocError>>()?;
 
     let handType = if WILDCARD_ACTIVE {
       Hand::<true>::get_hand_type(&cards)
@@ -270,17 +280,14 @@ impl<const WILDCARD_ACTIVE: bool> FromStr for Hand<WILDCARD_ACTIVE> {
   }
 }
 
-pub fn part_1(inputFile: Option<&str>) {
-  let inputFile = inputFile.unwrap_or("./inputs/day7part1.txt");
-  
-  let contents = fs::read_to_string(inputFile).expect("File should exist");
-
-  let mut hands: Vec<Hand<false>> = vec![];
+/// Every hand parsed twice, once per ruleset, since the hand type depends on whether jokers are wild.
+pub struct CamelCards {
+  hands: Vec<Hand<false>>,
+  wildcard_hands: Vec<Hand<true>>
+}
 
-  for line in contents.lines() {
-    let hand = Hand::from_str(line).unwrap();
-    hands.push(hand);
-  }
+fn get_total_winnings<const WILDCARD_ACTIVE: bool>(hands: &[Hand<WILDCARD_ACTIVE>]) -> i32 {
+  let mut hands: Vec<&Hand<WILDCARD_ACTIVE>> = hands.iter().collect();
 
   hands.sort();
 
@@ -289,51 +296,135 @@ pub fn part_1(inputFile: Option<&str>) {
   for (index, hand) in hands.iter().enumerate() {
     totalWinnings += (index+1) as i32 * hand.bid;    
   }
-  println!("Day 7 Part 1: {}", totalWinnings);
+
+  totalWinnings
 }
 
-pub fn part_2(inputFile: Option<&str>) {
-  let inputFile = inputFile.unwrap_or("./inputs/day7part1.txt");
+pub struct Day7;
 
-  let contents = fs::read_to_string(inputFile).expect("File should exist");
-  
-  let mut hands: Vec<Hand<true>> = vec![];
+impl Solution for Day7 {
+  const DAY: u8 = 7;
+
+  type Parsed<'a> = CamelCards;
+
+  fn parse(input: &str) -> Result<Self::Parsed<'_>, AocError> {
+    let mut cards = CamelCards { hands: vec![], wildcard_hands: vec![] };
+
+    for line in input.lines() {
+      cards.hands.push(Hand::from_str(line).map_err(|error| error.within(input, line))?);
+      cards.wildcard_hands.push(Hand::from_str(line).map_err(|error| error.within(input, line))?);
+    }
 
-  for line in contents.lines() {
-    let hand = Hand::from_str(line).unwrap();
-    hands.push(hand);
+    Ok(cards)
   }
 
-  hands.sort();
+  fn part_1(cards: &Self::Parsed<'_>) -> Result<Answer, AocError> {
+    let totalWinnings = get_total_winnings(&cards.hands);
 
-  let mut totalWinnings = 0;
+    Ok(totalWinnings.into())
+  }
 
-  for (index, hand) in hands.iter().enumerate() {
-    totalWinnings += (index+1) as i32 * hand.bid;    
+  fn part_2(cards: &Self::Parsed<'_>) -> Result<Answer, AocError> {
+    let totalWinnings = get_total_winnings(&cards.wildcard_hands);
+
+    Ok(totalWinnings.into())
   }
-  println!("Day 7 Part 2: {}", totalWinnings);
 }
 
+pub fn part_1(inputFile: Option<&str>) -> Result<Answer, AocError> {
+  solution::run_part_1::<Day7>(inputFile)
+}
 
-#[cfg(test)]
-mod tests {
-  use super::*;
+pub fn part_2(inputFile: Option<&str>) -> Result<Answer, AocError> {
+  solution::run_part_2::<Day7>(inputFile)
+}
 
-  #[test]
-  fn part_1_using_test_file() {
-    let inputFile = "./inputs/day7test.txt";
+const LABELS: &str = "23456789TJQKA";
 
-    part_1(Some(inputFile));
+/// Ranks hands by brute force: every joker is tried as every other label, and hand types come
+/// from the sorted label counts rather than any special cases.
+fn naive_winnings(input: &str, wildcard: bool) -> Option<i64> {
+  let order = if wildcard { "J23456789TQKA" } else { LABELS };
+
+  let mut hands = vec![];
+
+  for line in input.lines() {
+    let (cards, bid) = line.split_once(' ')?;
+    let bid: i64 = bid.parse().ok()?;
+
+    if cards.len() != 5 || !cards.chars().all(|card| LABELS.contains(card)) || hands.iter().any(|(other, _)| *other == cards) {
+      return None;
+    }
 
-    // should print 6440
+    hands.push((cards, bid));
   }
 
-  #[test]
-  fn part_2_using_test_file() {
-    let inputFile = "./inputs/day7test.txt";
+  let hand_type = |cards: &str| -> u8 {
+    let mut counts: Vec<usize> = LABELS.chars().map(|label| cards.chars().filter(|card| *card == label).count()).collect();
+    counts.sort_unstable_by(|a, b| b.cmp(a));
+
+    match (counts[0], counts[1]) {
+      (5, _) => 7,
+      (4, _) => 6,
+      (3, 2) => 5,
+      (3, _) => 4,
+      (2, 2) => 3,
+      (2, _) => 2,
+      _ => 1,
+    }
+  };
+
+  let best_type = |cards: &str| -> u8 {
+    match wildcard {
+      true => LABELS.chars().map(|label| hand_type(&cards.replace('J', &label.to_string()))).max().unwrap(),
+      false => hand_type(cards),
+    }
+  };
+
+  let mut ranked: Vec<(u8, Vec<usize>, i64)> = hands.iter()
+    .map(|(cards, bid)| (best_type(cards), cards.chars().map(|card| order.find(card).unwrap()).collect(), *bid))
+    .collect();
+  ranked.sort();
+
+  Some(ranked.iter().enumerate().map(|(index, (_, _, bid))| (index as i64 + 1) * bid).sum())
+}
+
+impl Fuzz for Day7 {
+  /// Hands are drawn from a few labels with plenty of jokers, so every hand type and joker case comes up.
+  fn generate(rng: &mut Rng) -> String {
+    let labels: Vec<char> = LABELS.chars().filter(|label| *label != 'J').collect();
+    let count = rng.range(1..12) as usize;
+    let mut hands: Vec<String> = vec![];
 
-    part_2(Some(inputFile));
+    while hands.len() < count {
+      let mut pool = labels.clone();
+      rng.shuffle(&mut pool);
+      pool.truncate(rng.range(1..6) as usize);
 
-    // should print 5905
+      let cards: String = (0..5).map(|_| if rng.chance(25) { 'J' } else { *rng.pick(&pool) }).collect();
+
+      if !hands.iter().any(|hand| hand.starts_with(&cards)) {
+        hands.push(format!("{} {}\n", cards, rng.range(1..1000)));
+      }
+    }
+
+    hands.concat()
+  }
+
+  fn oracle(input: &str, part: u8) -> Option<Answer> {
+    naive_winnings(input, part == 2).map(Answer::from)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn unhandled_cards_report_their_position() {
+    let error = Day7::parse("32T3K 765\nT55X5 684").err().unwrap();
+
+    assert_eq!(error.message, "Unhandled card 'X'");
+    assert_eq!((error.line, error.column), (2, 4));
   }
-}
\ No newline at end of file
+}
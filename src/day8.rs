@@ -1,18 +1,25 @@
 use std::collections::HashMap;
-use std::{fs, thread};
-use std::string::ParseError;
-use std::str::FromStr;
+use std::thread;
+
+use crate::repl::{self, Inspect};
+use crate::solution::{self, Answer, Solution};
+use crate::utils::error::AocError;
+use crate::utils::geom::Turn;
+use crate::utils::input::Input;
+use crate::utils::math;
+use crate::utils::parser::{self, delimited, identifier, literal, pair, spaces, terminated};
+use crate::utils::trace;
 
 #[derive(Clone)]
 struct Instructions {
-  set: Vec<char>,
+  set: Vec<Turn>,
   currentInstruction: usize
 }
 
 impl Instructions {
-  fn next(&mut self) -> char {
+  fn next(&mut self) -> Turn {
 
-    let nextInstruction = self.set.get(self.currentInstruction).unwrap().clone();
+    let nextInstruction = self.set[self.currentInstruction];
     self.currentInstruction += 1;
 
     if self.currentInstruction >= self.set.len() {
@@ -23,11 +30,16 @@ impl Instructions {
   }
 }
 
-impl FromStr for Instructions {
-  type Err = ParseError;
+impl Instructions {
+  fn parse(s: &str) -> Result<Self, AocError> {
+    if s.is_empty() {
+      return Err(AocError::end_of_input("Expected a line of L/R instructions", s));
+    }
+
+    let set = s.char_indices()
+      .map(|(index, c)| Turn::try_from(c).map_err(|_| AocError::new(format!("Unexpected instruction '{}'", c), s, &s[index..index + c.len_utf8()])))
+      .collect::<Result<Vec<Turn>, AocError>>()?;
 
-  fn from_str(s: &str) -> Result<Self, Self::Err> {
-    let set: Vec<char> = s.chars().collect();
     Ok(Instructions {
       set,
       currentInstruction: 0
@@ -35,200 +47,186 @@ impl FromStr for Instructions {
   } 
 }
 
-#[derive(Clone, Debug)]
-struct Node {
-  key: String,
-  left: String,
-  right: String
+#[derive(Clone, Copy, Debug)]
+struct Node<'a> {
+  key: &'a str,
+  left: &'a str,
+  right: &'a str
 }
 
-impl Node {
-  fn goDirection(self: &Self, direction: char) -> String {
+impl<'a> Node<'a> {
+  fn goDirection(self: &Self, direction: Turn) -> &'a str {
     match direction {
-      'L' => self.left.to_string(),
-      'R' => self.right.to_string(),
-      _ => panic!("Unexpected direction")
+      Turn::Left => self.left,
+      Turn::Right => self.right,
     }
   }
 }
 
-impl FromStr for Node {
-  type Err = ParseError;
-
-  fn from_str(s: &str) -> Result<Self, Self::Err> {
-    let key = s.chars().take(3).collect();
-    let left = s.chars().skip(7).take(3).collect();
-    let right = s.chars().skip(12).take(3).collect();
+impl<'a> Node<'a> {
+  fn parse(s: &'a str) -> Result<Self, AocError> {
+    let destinations = pair(terminated(identifier, pair(literal(","), spaces)), identifier);
+    let node = pair(
+      terminated(identifier, delimited(spaces, literal("="), spaces)),
+      delimited(literal("("), destinations, literal(")")),
+    );
 
-      Ok(Node {
-        key,
-        left,
-        right
-      })
-  }
-}
+    let (key, (left, right)) = parser::complete(s, node)?;
 
-fn is_prime_number(number: i32) -> bool {
-  for divisor in 2..number {
-    if number % divisor == 0 {
-      return false;
-    }
+    Ok(Node { key, left, right })
   }
-
-  true
 }
 
-fn get_primes_up_to(limit: i32) -> Vec<i32> {
-  let mut prime_numbers = vec![];
-  for i in 2..=limit {
-    if is_prime_number(i) {
-      prime_numbers.push(i);
-    }
-  }
-
-  prime_numbers
+pub struct Network<'a> {
+  instructions: Instructions,
+  nodes: HashMap<&'a str, Node<'a>>
 }
 
-pub fn part_1(inputFile: Option<&str>) {
-  let inputFile = inputFile.unwrap_or("./inputs/day8part1.txt");
-  
-  let contents = fs::read_to_string(inputFile).expect("File should exist");
+pub struct Day8;
 
-  let mut lines = contents.lines();
+impl Solution for Day8 {
+  const DAY: u8 = 8;
 
-  let mut instructions = Instructions::from_str(lines.next().unwrap()).unwrap();
-  
-  let _ = lines.next();
-  
-  let mut nodes: HashMap<String, Node> = HashMap::new();
+  type Parsed<'a> = Network<'a>;
 
-  for line in lines {
-    let node = Node::from_str(line).unwrap();
-    nodes.insert(node.key.to_string(), node);
-  }
+  fn parse(raw: &str) -> Result<Self::Parsed<'_>, AocError> {
+    let input = Input::new(raw);
+    let mut sections = input.sections();
 
-  let starting_node_key: String = "AAA".to_string();
-  let target_node_key: String = "ZZZ".to_string();
+    let instructions_span = trace::span("instructions");
+    let instructionLine = sections.next().map(|section| section.text).unwrap_or("");
+    let instructions = Instructions::parse(instructionLine).map_err(|error| error.within(input.text(), instructionLine))?;
+    drop(instructions_span);
 
-  let starting_node = nodes.get(&starting_node_key).unwrap();
-  let target_node = nodes.get(&target_node_key).unwrap();
-  let mut current_node = starting_node;
+    let _span = trace::span("nodes");
+    let mut nodes: HashMap<&str, Node> = HashMap::new();
+    let mut lines = vec![];
 
-  let mut travel_count: u64 = 0;
+    for line in sections.flat_map(|section| section.lines()) {
+      let node = Node::parse(line).map_err(|error| error.within(input.text(), line))?;
+      nodes.insert(node.key, node);
+      lines.push((line, node));
+    }
 
-  while current_node.key != target_node.key {
+    // Walks look nodes up as they go, so every reference has to lead somewhere
+    for (line, node) in &lines {
+      if let Some(missing) = [node.left, node.right].into_iter().find(|target| !nodes.contains_key(target)) {
+        return Err(AocError::new(format!("No node '{}'", missing), line, missing).within(input.text(), line));
+      }
+    }
 
-    let direction = instructions.next();
-    let next_node_key = current_node.goDirection(direction);
-    travel_count += 1;
+    if let Some(start) = nodes.get("AAA") {
+      if !nodes.contains_key("ZZZ") {
+        return Err(AocError::new("'AAA' has no 'ZZZ' node to walk to", input.text(), start.key));
+      }
+    }
 
-    current_node = nodes.get(&next_node_key).unwrap();
+    Ok(Network { instructions, nodes })
   }
 
-  println!("Day 8 Part 1: {}", travel_count);
-}
+  fn part_1(network: &Self::Parsed<'_>) -> Result<Answer, AocError> {
+    let _span = trace::span("walk");
+    let nodes = &network.nodes;
+    let mut instructions = network.instructions.clone();
 
-pub fn part_2(inputFile: Option<&str>) {
-  let inputFile = inputFile.unwrap_or("./inputs/day8part1.txt");
+    // Part 2's example has no AAA, so there is no part 1 walk to take
+    let Some(mut current_node) = nodes.get("AAA") else {
+      return Ok(Answer::Unsolved);
+    };
 
-  let contents = fs::read_to_string(inputFile).expect("File should exist");
-    
-  let mut lines = contents.lines();
+    let mut travel_count: u64 = 0;
 
-  let instructions = Instructions::from_str(lines.next().unwrap()).unwrap();
-  
-  let _ = lines.next();
-  
-  let mut nodes: HashMap<String, Node> = HashMap::new();
-  let mut start_nodes: Vec<Node> = vec![];
+    while current_node.key != "ZZZ" {
 
-  for line in lines {
-    let node = Node::from_str(line).unwrap();
-    
-    if node.key.ends_with('A') {
-      start_nodes.push(node.clone());
-    }
+      let direction = instructions.next();
+      let next_node_key = current_node.goDirection(direction);
+      travel_count += 1;
 
-    nodes.insert(node.key.to_string(), node);
+      current_node = &nodes[next_node_key];
+    }
 
+    Ok(travel_count.into())
   }
 
-  let mut handles = vec![];
+  fn part_2(network: &Self::Parsed<'_>) -> Result<Answer, AocError> {
+    let start_nodes: Vec<&Node> = network.nodes.values().filter(|node| node.key.ends_with('A')).collect();
 
-  for node in start_nodes {
-    let handle = thread::spawn({ 
-      
-      let starting_node = node.clone();
-      let nodes = nodes.clone();
+    // Nodes borrow from the input, so the walks run on scoped threads instead of cloning the map
+    let travelCounts: Vec<u64> = thread::scope(|scope| {
+      let spawn_span = trace::span("spawn walks");
+      let mut handles = vec![];
 
-      // Target node is any that end in Z      
-      let mut current_node = starting_node;
-      let mut instructions = instructions.clone();
-      move || {
+      for node in start_nodes {
+        let handle = scope.spawn({
 
-        let mut travel_count = 0;
+          // Target node is any that end in Z
+          let mut current_node = node;
+          let mut instructions = network.instructions.clone();
+          let nodes = &network.nodes;
+          move || {
 
-        while !current_node.key.ends_with('Z') {
-          let direction = instructions.next();
-          let next_node_key = current_node.goDirection(direction);
-          travel_count += 1;
+            let mut travel_count: u64 = 0;
 
-          current_node = nodes.get(&next_node_key).unwrap().clone();
-        }
+            while !current_node.key.ends_with('Z') {
+              let direction = instructions.next();
+              let next_node_key = current_node.goDirection(direction);
+              travel_count += 1;
 
-        travel_count
-      }
-    }); 
+              current_node = &nodes[next_node_key];
+            }
 
-    handles.push(handle);
-  }
+            travel_count
+          }
+        });
 
-  let mut travelCounts: Vec<i32> = vec![];
+        handles.push(handle);
+      }
+      drop(spawn_span);
 
-  for handle in handles {
-    let handleValue = handle.join().unwrap();
-    travelCounts.push(handleValue);
-  }
-  
-  // Solve for LCM
+      let _span = trace::span("join threads");
+      handles.into_iter().map(|handle| handle.join().unwrap()).collect()
+    });
+    
+    // Solve for LCM
+    let _span = trace::span("lcm");
 
-  let prime_numbers: Vec<i32> = get_primes_up_to(500);
-  let mut max_prime_factors = HashMap::new();
+    let least_common_multiple = math::lcm_all(travelCounts).expect("The walks' LCM should fit in a u64");
 
-  for distance in travelCounts {
-    
-    let mut prime_factors = HashMap::new();
+    Ok(least_common_multiple.into())
+  }
+}
 
-    let mut remainingDistance = distance;
+impl Inspect for Day8 {
+  const QUERIES: &'static [&'static str] = &[
+    "node <key>          where a node leads and which nodes lead to it",
+  ];
 
-    for number in prime_numbers.iter() {
+  fn query(network: &Self::Parsed<'_>, query: &str, args: &str) -> Result<String, String> {
+    match query {
+      "node" => {
+        let node = network.nodes.get(args).ok_or_else(|| format!("No node '{}'", args))?;
 
-      if remainingDistance == 1 {
-        break;
-      }
-      
-      while remainingDistance % number == 0 {
-        *prime_factors.entry(number).or_insert(0) += 1;
-        remainingDistance = remainingDistance / number;
-      }
-    }
+        let mut sources: Vec<&str> = network.nodes.values().filter(|other| other.left == node.key || other.right == node.key).map(|other| other.key).collect();
+        sources.sort_unstable();
 
-    for (factor, power) in prime_factors {
-      let max_power: i32 = *max_prime_factors.get(factor).unwrap_or(&0);
+        let sources = match sources.is_empty() {
+          true => "reached from nowhere".to_string(),
+          false => format!("reached from {}", sources.join(", ")),
+        };
 
-      if max_power < power {
-        *max_prime_factors.entry(factor).or_insert(0) = power;
-      }
+        Ok(format!("{} = ({}, {}), {}", node.key, node.left, node.right, sources))
+      },
+      _ => Err(repl::unknown(query)),
     }
   }
+}
 
-  let mut least_common_multiple = 1;
-
-  for (factor, power) in max_prime_factors.into_iter() {
-    least_common_multiple *= u64::pow(*factor as u64, power as u32);
-  }
+pub fn part_1(inputFile: Option<&str>) -> Result<Answer, AocError> {
+  solution::run_part_1::<Day8>(inputFile)
+}
 
-  println!("Day 8 Part 2: {}", least_common_multiple);
+pub fn part_2(inputFile: Option<&str>) -> Result<Answer, AocError> {
+  solution::run_part_2::<Day8>(inputFile)
 }
 
 #[cfg(test)]
@@ -236,20 +234,13 @@ mod tests {
   use super::*;
 
   #[test]
-  fn part_1_using_test_file() {
-    let inputFile = "./inputs/day8test.txt";
-
-    part_1(Some(inputFile));
-
-    // should print 6
+  fn missing_nodes_are_reported_where_they_are_referenced() {
+    let error = Day8::parse("LR\n\nAAA = (BBB, ZZZ)\nBBB = (CCC, ZZZ)\nZZZ = (ZZZ, ZZZ)").err().unwrap();
+    assert_eq!(error.message, "No node 'CCC'");
+    assert_eq!((error.line, error.column), (4, 8));
+
+    let error = Day8::parse("LR\n\nAAA = (AAA, AAA)").err().unwrap();
+    assert_eq!(error.message, "'AAA' has no 'ZZZ' node to walk to");
+    assert_eq!((error.line, error.column), (3, 1));
   }
-
-  #[test]
-  fn part_2_using_test_file() {
-    let inputFile = "./inputs/day8test.txt";
-
-    part_2(Some(inputFile));
-
-    // should print 5905
-  }
-}
\ No newline at end of file
+}
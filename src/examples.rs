@@ -0,0 +1,65 @@
+//! Every day's puzzle examples paired with the answers given in the puzzle text.
+//!
+//! Each entry names the input variant it reads (`test` is `dayNtest.txt`) so days whose two
+//! parts use different examples can point at separate files.
+
+use crate::solution::Answer;
+use crate::utils::input_resolver::{InputKind, InputResolver};
+use crate::REGISTRY;
+
+fn assert_example(day: u8, variant: &str, part: u8, expected: Answer) {
+  let registered = REGISTRY.iter()
+    .find(|registered| registered.day == day)
+    .unwrap_or_else(|| panic!("Day {} is not registered", day));
+
+  let kind: InputKind = variant.parse().unwrap();
+  let inputFile = InputResolver::current().path(day, &kind);
+
+  let run = match part {
+    1 => registered.part_1,
+    _ => registered.part_2,
+  };
+
+  match run(Some(&inputFile)) {
+    Ok(answer) => assert_eq!(answer, expected, "Day {} Part {} on {}", day, part, inputFile),
+    Err(error) => panic!("Day {} Part {} on {} failed\n{}", day, part, inputFile, error),
+  }
+}
+
+/// `name: day, variant, part => expected` expands into one test per example.
+macro_rules! examples {
+  ($($(#[$attribute:meta])* $name:ident: $day:literal, $variant:literal, $part:literal => $expected:expr;)*) => {
+    $(
+      #[test]
+      $(#[$attribute])*
+      fn $name() {
+        assert_example($day, $variant, $part, Answer::from($expected));
+      }
+    )*
+  };
+}
+
+examples! {
+  day1_part1: 1, "test", 1 => 142;
+  day1_part2: 1, "test2", 2 => 281;
+
+  day2_part1: 2, "test", 1 => 8;
+  day2_part2: 2, "test", 2 => 2286;
+
+  day3_part1: 3, "test", 1 => 4361;
+
+  day4_part1: 4, "test", 1 => 13;
+  day4_part2: 4, "test", 2 => 30;
+
+  day5_part1: 5, "test", 1 => 35;
+  day5_part2: 5, "test", 2 => 46;
+
+  day6_part1: 6, "test", 1 => 288;
+  day6_part2: 6, "test", 2 => 71503;
+
+  day7_part1: 7, "test", 1 => 6440;
+  day7_part2: 7, "test", 2 => 5905;
+
+  day8_part1: 8, "test", 1 => 6;
+  day8_part2: 8, "test2", 2 => 6;
+}
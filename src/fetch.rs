@@ -0,0 +1,89 @@
+use std::fs;
+use std::path::Path;
+
+use crate::config::{Config, YEAR};
+use crate::utils::http::{self, Url};
+use crate::utils::input_resolver::{InputKind, InputResolver};
+
+pub const USER_AGENT: &str = "AdventOfRust2023 (github.com/UhmBrock/advent-of-code-2023)";
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Fetched {
+  /// The input was already on disk, nothing was downloaded.
+  Cached(String),
+  Downloaded(String),
+}
+
+/// Makes sure the day's input is in the input directory, downloading it only if it isn't there yet.
+/// An empty file, such as the placeholder from `new-day`, counts as missing.
+pub fn fetch_input(day: u8, config: &Config, resolver: &InputResolver) -> Result<Fetched, String> {
+  let inputFile = resolver.path(day, &InputKind::Real);
+
+  if fs::metadata(&inputFile).is_ok_and(|metadata| metadata.len() > 0) {
+    return Ok(Fetched::Cached(inputFile));
+  }
+
+  let url = Url::parse(config.base_url()?)?.join(&format!("{}/day/{}/input", YEAR, day));
+  let cookie = format!("session={}", config.session()?);
+
+  let response = http::request("GET", &url, &[("Cookie", &cookie), ("User-Agent", USER_AGENT)], None)?;
+
+  match response.status {
+    200 if !response.body.is_empty() => {},
+    200 => return Err(format!("The server sent an empty input for day {}", day)),
+    300..=399 => return Err(format!("Redirected to {}", response.header("Location").unwrap_or("an unknown location"))),
+    400 | 401 | 403 | 500 => return Err(format!("The session cookie was rejected (HTTP {})", response.status)),
+    404 => return Err(format!("Day {}'s input isn't available yet (HTTP 404)", day)),
+    status => return Err(format!("Unexpected HTTP {} fetching day {}", status, day)),
+  }
+
+  fs::create_dir_all(resolver.directory()).map_err(|error| format!("Could not create {}: {}", resolver.directory(), error))?;
+
+  // Write alongside and rename so an interrupted download never looks like a cached input
+  let partial = format!("{}.partial", inputFile);
+  fs::write(&partial, &response.body)
+    .and_then(|_| fs::rename(&partial, Path::new(&inputFile)))
+    .map_err(|error| format!("Could not write {}: {}", inputFile, error))?;
+
+  Ok(Fetched::Downloaded(inputFile))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::io::{Read, Write};
+  use std::net::TcpListener;
+  use std::thread;
+
+  #[test]
+  fn inputs_are_downloaded_once_and_then_cached() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    // Serves exactly one request, so a second download would fail to connect or hang
+    let server = thread::spawn(move || {
+      let (mut stream, _) = listener.accept().unwrap();
+      let mut request = [0; 1024];
+      let read = stream.read(&mut request).unwrap();
+      stream.write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 8\r\n\r\n1 2 3 4\n").unwrap();
+      String::from_utf8_lossy(&request[..read]).to_string()
+    });
+
+    let directory = std::env::temp_dir().join(format!("aoc_fetch_{}", std::process::id()));
+    let resolver = InputResolver::new(&directory.to_string_lossy());
+    let config = Config { session: Some("s3cret".to_string()), base_url: Some(format!("http://127.0.0.1:{}", port)) };
+
+    let first = fetch_input(9, &config, &resolver).unwrap();
+    let request = server.join().unwrap();
+    let second = fetch_input(9, &config, &resolver).unwrap();
+    let contents = fs::read_to_string(resolver.path(9, &InputKind::Real)).unwrap();
+
+    fs::remove_dir_all(&directory).unwrap();
+
+    assert!(request.starts_with("GET /2023/day/9/input HTTP/1.1\r\n"));
+    assert!(request.contains("Cookie: session=s3cret\r\n"));
+    assert_eq!(first, Fetched::Downloaded(resolver.path(9, &InputKind::Real)));
+    assert_eq!(second, Fetched::Cached(resolver.path(9, &InputKind::Real)));
+    assert_eq!(contents, "1 2 3 4\n");
+  }
+}
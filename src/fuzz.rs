@@ -0,0 +1,234 @@
+use std::ops::Range;
+use std::panic;
+
+use crate::cli::FuzzOptions;
+use crate::day4::Day4;
+use crate::day5::Day5;
+use crate::day7::Day7;
+use crate::solution::{self, Answer, Solution};
+use crate::utils::rng::Rng;
+use crate::utils::thread_pool;
+
+/// A day the fuzzer can check: a generator for random valid inputs, and a deliberately naive
+/// solution to hold the real one against.
+pub trait Fuzz: Solution {
+  /// The parts worth fuzzing. A part that doesn't finish quickly on any input has to be left out.
+  const PARTS: &'static [u8] = &[1, 2];
+
+  /// Builds a random input that follows the puzzle's rules.
+  fn generate(rng: &mut Rng) -> String;
+
+  /// Solves `input` the slow, obvious way. `None` if the input breaks the puzzle's rules, so
+  /// shrinking never settles on an input the real solution was never meant to handle.
+  fn oracle(input: &str, part: u8) -> Option<Answer>;
+}
+
+pub type Fuzzer = fn(&FuzzOptions) -> Result<Vec<u8>, Failure>;
+
+/// Every day with a `Fuzz` implementation.
+pub const FUZZERS: &[(u8, Fuzzer)] = &[
+  (4, fuzz::<Day4>),
+  (5, fuzz::<Day5>),
+  (7, fuzz::<Day7>),
+];
+
+/// A generated input the solution and oracle disagree on, already shrunk.
+#[derive(Debug)]
+pub struct Failure {
+  pub part: u8,
+  /// Seed of the failing iteration, so `--seed <seed> --iterations 1` replays it.
+  pub seed: u64,
+  pub input: String,
+  pub expected: Answer,
+  pub actual: String,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Verdict {
+  Agree,
+  Disagree { expected: Answer, actual: String },
+  /// The oracle rejected the input.
+  Invalid,
+}
+
+/// Runs `options.iterations` random inputs through the solution and the oracle, iteration `i`
+/// seeded with `options.seed + i`. Returns the parts that were checked.
+pub fn fuzz<F: Fuzz>(options: &FuzzOptions) -> Result<Vec<u8>, Failure> {
+  let parts: Vec<u8> = F::PARTS.iter().copied().filter(|part| options.part.is_none_or(|selected| selected == *part)).collect();
+
+  if parts.is_empty() {
+    return Ok(parts);
+  }
+
+  // A panicking solution is a disagreement like any other, keep the default hook from printing each one
+  let result = thread_pool::without_panic_output(|| (0..options.iterations as u64).try_for_each(|iteration| {
+    let seed = options.seed.wrapping_add(iteration);
+    let input = F::generate(&mut Rng::new(seed));
+
+    for part in parts.iter().copied() {
+      if let Verdict::Disagree { .. } = check::<F>(&input, part) {
+        let input = shrink::<F>(input, part);
+
+        let Verdict::Disagree { expected, actual } = check::<F>(&input, part) else {
+          unreachable!("Shrinking only keeps failing inputs");
+        };
+
+        return Err(Failure { part, seed, input, expected, actual });
+      }
+    }
+
+    Ok(())
+  }));
+
+  result.map(|_| parts)
+}
+
+fn check<F: Fuzz>(input: &str, part: u8) -> Verdict {
+  let expected = match panic::catch_unwind(|| F::oracle(input, part)) {
+    Ok(Some(expected)) => expected,
+    _ => return Verdict::Invalid,
+  };
+
+  let actual = panic::catch_unwind(|| F::parse(input).and_then(|parsed| solution::solve::<F>(&parsed, part)));
+
+  let actual = match actual {
+    Ok(Ok(answer)) if answer == expected => return Verdict::Agree,
+    Ok(Ok(answer)) => answer.to_string(),
+    Ok(Err(error)) => format!("error: {}", error.message),
+    Err(payload) => format!("panicked: {}", thread_pool::panic_message(payload)),
+  };
+
+  Verdict::Disagree { expected, actual }
+}
+
+/// Keeps taking the first smaller candidate that still disagrees until none do.
+fn shrink<F: Fuzz>(mut input: String, part: u8) -> String {
+  'shrinking: loop {
+    for candidate in candidates(&input) {
+      if let Verdict::Disagree { .. } = check::<F>(&candidate, part) {
+        input = candidate;
+        continue 'shrinking;
+      }
+    }
+
+    return input;
+  }
+}
+
+/// Smaller versions of `input`: each line left out, then each number brought closer to zero.
+fn candidates(input: &str) -> Vec<String> {
+  let lines: Vec<&str> = input.lines().collect();
+  let mut candidates = vec![];
+
+  for skipped in 0..lines.len() {
+    let kept: Vec<&str> = lines.iter().enumerate().filter(|(index, _)| *index != skipped).map(|(_, line)| *line).collect();
+    candidates.push(kept.iter().map(|line| format!("{}\n", line)).collect());
+  }
+
+  for range in number_tokens(input) {
+    let Ok(value) = input[range.clone()].parse::<u64>() else {
+      continue;
+    };
+
+    let mut smaller = vec![0, value / 2, value.saturating_sub(1)];
+    smaller.dedup();
+
+    for replacement in smaller.into_iter().filter(|replacement| *replacement < value) {
+      candidates.push(format!("{}{}{}", &input[..range.start], replacement, &input[range.end..]));
+    }
+  }
+
+  candidates
+}
+
+/// Where the whitespace separated tokens made only of digits are.
+fn number_tokens(input: &str) -> Vec<Range<usize>> {
+  let bytes = input.as_bytes();
+  let mut tokens = vec![];
+  let mut start = 0;
+
+  while start < bytes.len() {
+    if bytes[start].is_ascii_whitespace() {
+      start += 1;
+      continue;
+    }
+
+    let mut end = start;
+    while end < bytes.len() && !bytes[end].is_ascii_whitespace() {
+      end += 1;
+    }
+
+    if bytes[start..end].iter().all(|byte| byte.is_ascii_digit()) {
+      tokens.push(start..end);
+    }
+
+    start = end;
+  }
+
+  tokens
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::utils::error::{self, AocError};
+
+  /// Sums one number per line, but forgets anything over 50.
+  struct Forgetful;
+
+  impl Solution for Forgetful {
+    const DAY: u8 = 0;
+
+    type Parsed<'a> = Vec<u64>;
+
+    fn parse(input: &str) -> Result<Self::Parsed<'_>, AocError> {
+      input.lines().map(|line| error::unsigned_at(input, line)).collect()
+    }
+
+    fn part_1(numbers: &Self::Parsed<'_>) -> Result<Answer, AocError> {
+      Ok(numbers.iter().filter(|number| **number <= 50).sum::<u64>().into())
+    }
+
+    fn part_2(_numbers: &Self::Parsed<'_>) -> Result<Answer, AocError> {
+      Ok(Answer::Unsolved)
+    }
+  }
+
+  impl Fuzz for Forgetful {
+    const PARTS: &'static [u8] = &[1];
+
+    fn generate(rng: &mut Rng) -> String {
+      (0..rng.range(1..10)).map(|_| format!("{}\n", rng.range(0..100))).collect()
+    }
+
+    fn oracle(input: &str, _part: u8) -> Option<Answer> {
+      let numbers = input.lines().map(|line| line.parse::<u64>().ok()).collect::<Option<Vec<u64>>>()?;
+      Some(numbers.iter().sum::<u64>().into())
+    }
+  }
+
+  #[test]
+  fn disagreements_shrink_to_a_minimal_input() {
+    let failure = fuzz::<Forgetful>(&FuzzOptions { day: 0, part: None, iterations: 50, seed: 7 }).unwrap_err();
+
+    assert_eq!(failure.part, 1);
+    assert_eq!(failure.input, "51\n");
+    assert_eq!(failure.expected, Answer::Number(51));
+    assert_eq!(failure.actual, "0");
+  }
+
+  #[test]
+  fn candidates_drop_lines_and_shrink_numbers() {
+    assert_eq!(number_tokens("32T3K 765\nKK677 8"), vec![6..9, 16..17]);
+    assert_eq!(candidates("a 3\nb\n"), vec!["b\n", "a 3\n", "a 0\nb\n", "a 1\nb\n", "a 2\nb\n"]);
+  }
+
+  #[test]
+  fn fuzzed_days_agree_with_their_oracles() {
+    for (day, fuzzer) in FUZZERS.iter() {
+      let result = fuzzer(&FuzzOptions { day: *day, part: None, iterations: 30, seed: 2023 });
+
+      assert!(result.is_ok(), "Day {} disagreed with its oracle: {:?}", day, result.err());
+    }
+  }
+}
@@ -1,49 +1,466 @@
 #![allow(non_snake_case)]
+#![allow(clippy::needless_return, clippy::needless_arbitrary_self_type)]
 
-use std::thread::{self, JoinHandle};
+use std::{env, io, panic, process};
+use std::time::Instant;
 
+use answers::AnswerStore;
+use config::Config;
+use fetch::Fetched;
+use cli::{BenchOptions, Command, FetchOptions, FuzzOptions, RunOptions, SubmitOptions, WatchOptions};
+use report::{OutputFormat, PartResult};
+use scaffold::Scaffold;
+use submit::Outcome;
+use solution::{Answer, TimedRun};
+use utils::alloc;
+use utils::duration_utils::format_duration;
+use utils::error::AocError;
+use utils::input_resolver::InputResolver;
+use utils::thread_pool::{self, Job};
+use utils::trace;
+
+pub mod answers;
+pub mod bench;
+pub mod cli;
+pub mod config;
+#[cfg(test)]
+mod examples;
+pub mod fetch;
+pub mod fuzz;
+pub mod repl;
+pub mod report;
+pub mod scaffold;
+pub mod submit;
 pub mod utils;
-pub mod day1;
-pub mod day2;
-pub mod day3;
-pub mod day4;
-pub mod day5;
-pub mod day6;
-pub mod day7;
-pub mod day8;
+pub mod watch;
+#[macro_use]
+pub mod solution;
+
+register_days! {
+  day1 => Day1,
+  day2 => Day2,
+  day3 => Day3,
+  day4 => Day4,
+  day5 => Day5,
+  day6 => Day6,
+  day7 => Day7,
+  day8 => Day8,
+}
 
 fn main() {
 
-  let mut handles = vec![];
+  let command = match cli::parse_args(env::args().skip(1)) {
+    Ok(command) => command,
+    Err(message) => {
+      eprintln!("{}\n\n{}", message, cli::USAGE);
+      process::exit(2);
+    }
+  };
+
+  if let Command::Run(options) | Command::Verify(options) | Command::Record(options) | Command::Bench(BenchOptions { run: options, .. })
+    | Command::Watch(WatchOptions { run: options, .. }) | Command::Fetch(FetchOptions { run: options, .. })
+    | Command::Repl(options) = &command {
+    if let Some(directory) = &options.input_dir {
+      InputResolver::set_directory(directory);
+    }
+  }
+
+  match command {
+    Command::Run(options) => run(&options),
+    Command::Verify(options) => verify(&options),
+    Command::Record(options) => record(&options),
+    Command::Bench(options) => {
+      if let Err(message) = bench::run(&options, REGISTRY) {
+        eprintln!("{}", message);
+        process::exit(1);
+      }
+    },
+    Command::Watch(options) => {
+      if let Err(message) = watch::run(&options, REGISTRY) {
+        eprintln!("{}", message);
+        process::exit(1);
+      }
+    },
+    Command::Fetch(options) => fetch(&options),
+    Command::Submit(options) => submit(&options),
+    Command::Fuzz(options) => fuzz(&options),
+    Command::Repl(options) => repl(&options),
+    Command::NewDay(day) => new_day(day),
+    Command::Help => println!("{}", cli::USAGE),
+  }
+}
+
+fn run(options: &RunOptions) {
+  if options.trace {
+    trace::enable();
+  }
+
+  if let Some(workers) = options.parallel {
+    return run_parallel(options, workers);
+  }
+
+  let mut results = vec![];
+  let mut failed = false;
+
+  for registered in REGISTRY.iter().filter(|registered| options.days.contains(registered.day)) {
+    let inputFile = options.input_for(registered.day);
+
+    let parts: Vec<u8> = [1, 2].into_iter().filter(|part| options.runs_part(*part)).collect();
+
+    // A panicking day is reported like a failed one and the rest still run, as in parallel mode
+    let outcome = thread_pool::without_panic_output(|| panic::catch_unwind(|| (registered.timed)(Some(&inputFile), &parts)));
+
+    match outcome {
+      Ok(Ok(runs)) => {
+        for (part, run) in parts.into_iter().zip(runs) {
+          if options.format == OutputFormat::Text {
+            println!("Day {} Part {}: {}{}", registered.day, part, run.answer, alloc_summary(&run));
+          }
+          print_trace(registered.day, part, &run, options.format);
+          results.push(PartResult { day: registered.day, part, run });
+        }
+      },
+      Ok(Err(error)) => {
+        eprintln!("Day {} failed\n{}\n", registered.day, error);
+        failed = true;
+      },
+      Err(payload) => {
+        eprintln!("Day {} panicked: {}\n", registered.day, thread_pool::panic_message(payload));
+        failed = true;
+      },
+    }
+  }
+
+  match options.format {
+    OutputFormat::Text => println!("====================================="),
+    OutputFormat::Json => println!("{}", report::to_json(&results)),
+    OutputFormat::Csv => print!("{}", report::to_csv(&results)),
+  }
+
+  if failed {
+    process::exit(1);
+  }
+}
+
+/// Runs every selected part on a bounded pool, printing the results in day/part order once all have finished.
+fn run_parallel(options: &RunOptions, workers: usize) {
+  let mut labels = vec![];
+  let mut jobs: Vec<Job<Result<TimedRun, AocError>>> = vec![];
+
+  for registered in REGISTRY.iter().filter(|registered| options.days.contains(registered.day)) {
+    for part in [1, 2] {
+      if !options.runs_part(part) {
+        continue;
+      }
+
+      let inputFile = options.input_for(registered.day);
+      let timed = registered.timed;
+
+      labels.push((registered.day, part));
+      jobs.push(Box::new(move || timed(Some(&inputFile), &[part]).map(|mut runs| runs.remove(0))));
+    }
+  }
+
+  // Panics are reported with the results below, so keep the default hook from printing them mid-run
+  let start = Instant::now();
+  let outcomes = thread_pool::without_panic_output(|| thread_pool::run_bounded(jobs, workers));
+  let total = start.elapsed();
+
+  let mut results = vec![];
+  let mut failed = false;
+
+  for ((day, part), outcome) in labels.into_iter().zip(outcomes) {
+    match outcome {
+      Ok(Ok(run)) => {
+        if options.format == OutputFormat::Text {
+          println!("Day {} Part {}: {} ({}){}", day, part, run.answer, format_duration(run.parse + run.solve), alloc_summary(&run));
+        }
+        print_trace(day, part, &run, options.format);
+        results.push(PartResult { day, part, run });
+      },
+      Ok(Err(error)) => {
+        eprintln!("Day {} Part {} failed\n{}\n", day, part, error);
+        failed = true;
+      },
+      Err(message) => {
+        eprintln!("Day {} Part {} panicked: {}\n", day, part, message);
+        failed = true;
+      },
+    }
+  }
+
+  match options.format {
+    OutputFormat::Text => {
+      println!("=====================================");
+      println!("Finished in {} on {} worker(s)", format_duration(total), workers);
+      if alloc::is_enabled() && workers > 1 {
+        println!("Allocation counts include every part that was running at the same time");
+      }
+    },
+    OutputFormat::Json => println!("{}", report::to_json(&results)),
+    OutputFormat::Csv => print!("{}", report::to_csv(&results)),
+  }
+
+  if failed {
+    process::exit(1);
+  }
+}
+
+/// Allocation counts to print after the answer, empty without the `alloc-stats` feature.
+fn alloc_summary(run: &TimedRun) -> String {
+  match (run.parse_alloc, run.solve_alloc) {
+    (Some(parse), Some(solve)) => format!("  [parse: {}; solve: {}]", parse, solve),
+    _ => String::new(),
+  }
+}
+
+/// Prints the part's span tree under its answer, or to stderr when stdout is for scripts.
+fn print_trace(day: u8, part: u8, run: &TimedRun, format: OutputFormat) {
+  if run.trace.is_empty() {
+    return;
+  }
+
+  let tree = trace::render(&run.trace);
+
+  match format {
+    OutputFormat::Text => print!("{}", tree),
+    _ => eprint!("Day {} Part {}\n{}", day, part, tree),
+  }
+}
+
+/// Reruns every selected part against its default input and any input with a recorded answer,
+/// exiting non-zero if any answer no longer matches.
+fn verify(options: &RunOptions) {
+  let store = load_answers();
 
-  day1::part_1(None);    
-  day1::part_2(None);
-  day2::part_1(None);
-  day2::part_2(None);
-  day3::part_1(None);
-  day4::part_1(); 
-  handles.push(as_thread(day4::part_2));
-  day5::part_1();
-  // handles.push(as_thread(day5::part_2));
-  day6::part_1(None);
-  day6::part_2(None);
-  day7::part_1(None);
-  day7::part_2(None);
-  day8::part_1(None);
-  day8::part_2(None);
+  let mut failures = 0;
 
-  println!("=====================================");
+  println!("{:<5}{:<6}{:<24}{:<20}{:<20}Status", "Day", "Part", "Input", "Expected", "Actual");
 
+  for registered in REGISTRY.iter().filter(|registered| options.days.contains(registered.day)) {
 
-  if false {
-      for handle in handles {
-          handle.join().unwrap();
+    let inputs = match &options.input {
+      Some(input) => vec![input.clone()],
+      None => {
+        let mut inputs = vec![options.input_for(registered.day)];
+        for input in store.inputs_for_day(registered.day) {
+          if !inputs.contains(&input) {
+            inputs.push(input);
+          }
+        }
+        inputs
       }
+    };
+
+    for input in inputs.iter() {
+      for (part, function) in [(1, registered.part_1), (2, registered.part_2)] {
+        if !options.runs_part(part) {
+          continue;
+        }
+
+        let expected = store.get(registered.day, input, part);
+        let actual = panic::catch_unwind(|| function(Some(input.as_str())));
+
+        let (actual, status) = match (&actual, expected) {
+          (Err(_), _) => ("panicked".to_string(), "FAIL"),
+          (Ok(Err(error)), _) => {
+            eprintln!("{}", error);
+            ("error".to_string(), "FAIL")
+          },
+          (Ok(Ok(Answer::Unsolved)), None) => continue,
+          (Ok(Ok(answer)), None) => (answer.to_string(), "missing"),
+          (Ok(Ok(answer)), Some(expected)) if answer == expected => (answer.to_string(), "pass"),
+          (Ok(Ok(answer)), Some(_)) => (answer.to_string(), "FAIL"),
+        };
+
+        if status == "FAIL" {
+          failures += 1;
+        }
+
+        let expected = expected.map(|answer| answer.to_string()).unwrap_or_else(|| "-".to_string());
+        let inputName = InputResolver::current().relative(input).to_string();
+
+        println!("{:<5}{:<6}{:<24}{:<20}{:<20}{}", registered.day, part, inputName, expected, actual, status);
+      }
+    }
+  }
+
+  if failures > 0 {
+    eprintln!("{} answer(s) did not match {}", failures, answers::answers_file());
+    process::exit(1);
+  }
+}
+
+/// Runs the selected parts and stores their answers as known-correct.
+fn record(options: &RunOptions) {
+  let mut store = load_answers();
+
+  for registered in REGISTRY.iter().filter(|registered| options.days.contains(registered.day)) {
+    let input = options.input_for(registered.day);
+
+    for (part, function) in [(1, registered.part_1), (2, registered.part_2)] {
+      if !options.runs_part(part) {
+        continue;
+      }
+
+      let answer = match function(Some(input.as_str())) {
+        Ok(Answer::Unsolved) => continue,
+        Ok(answer) => answer,
+        Err(error) => {
+          eprintln!("Day {} Part {} failed\n{}\n", registered.day, part, error);
+          continue;
+        }
+      };
+
+      println!("Recorded Day {} Part {}: {}", registered.day, part, answer);
+      store.insert(registered.day, &input, part, answer);
+    }
+  }
+
+  let answersFile = answers::answers_file();
+
+  if let Err(error) = store.save(&answersFile) {
+    eprintln!("Could not write {}: {}", answersFile, error);
+    process::exit(1);
   }
 }
 
-fn as_thread(function: fn()) -> JoinHandle<()> {
-  thread::spawn(move || {
-      function()
-  })
-}
\ No newline at end of file
+fn fetch(options: &FetchOptions) {
+  let Some(day) = options.run.days.single() else {
+    return;
+  };
+
+  let result = Config::load().and_then(|mut config| {
+    if let Some(base_url) = &options.base_url {
+      config.base_url = Some(base_url.clone());
+    }
+    fetch::fetch_input(day, &config, &InputResolver::current())
+  });
+
+  match result {
+    Ok(Fetched::Cached(inputFile)) => println!("Day {} is already cached at {}, not downloading it again", day, inputFile),
+    Ok(Fetched::Downloaded(inputFile)) => println!("Saved day {} to {}", day, inputFile),
+    Err(message) => {
+      eprintln!("{}", message);
+      process::exit(1);
+    }
+  }
+}
+
+/// Runs the selected part and submits its answer, logging the attempt in the submission history.
+fn submit(options: &SubmitOptions) {
+  let (Some(day), Some(part)) = (options.run.days.single(), options.run.part) else {
+    return;
+  };
+
+  let Some(registered) = REGISTRY.iter().find(|registered| registered.day == day) else {
+    eprintln!("Day {} is not registered", day);
+    process::exit(1);
+  };
+
+  let function = if part == 1 { registered.part_1 } else { registered.part_2 };
+
+  let answer = match function(Some(&options.run.input_for(day))) {
+    Ok(answer) => answer,
+    Err(error) => {
+      eprintln!("Day {} Part {} failed\n{}\n", day, part, error);
+      process::exit(1);
+    }
+  };
+
+  println!("Submitting Day {} Part {}: {}", day, part, answer);
+
+  let result = Config::load().and_then(|mut config| {
+    if let Some(base_url) = &options.base_url {
+      config.base_url = Some(base_url.clone());
+    }
+    submit::submit(day, part, &answer, &config, &submit::history_file())
+  });
+
+  match result {
+    Ok(Outcome::Correct) => println!("Correct!"),
+    Ok(Outcome::TooHigh) => println!("Wrong, the answer is too high"),
+    Ok(Outcome::TooLow) => println!("Wrong, the answer is too low"),
+    Ok(Outcome::Wrong) => println!("Wrong"),
+    Ok(Outcome::RateLimited(wait)) => println!("Rate-limited, try again in {}s", wait.as_secs()),
+    Ok(Outcome::WrongLevel) => println!("The site isn't expecting this part: it is already solved, or part 1 isn't solved yet"),
+    Err(message) => {
+      eprintln!("{}", message);
+      process::exit(1);
+    }
+  }
+}
+
+/// Checks the day against its oracle on random inputs, printing the shrunk input if they disagree.
+fn fuzz(options: &FuzzOptions) {
+  let Some((_, fuzzer)) = fuzz::FUZZERS.iter().find(|(day, _)| *day == options.day) else {
+    eprintln!("Day {} has no fuzzer", options.day);
+    process::exit(2);
+  };
+
+  println!("Fuzzing day {} with {} input(s) from seed {}", options.day, options.iterations, options.seed);
+
+  match fuzzer(options) {
+    Ok(parts) if parts.is_empty() => {
+      eprintln!("Day {} can't fuzz part {}", options.day, options.part.unwrap_or_default());
+      process::exit(2);
+    },
+    Ok(parts) => {
+      let parts: Vec<String> = parts.iter().map(|part| part.to_string()).collect();
+      println!("Part(s) {} agreed with the oracle on every input", parts.join(", "));
+    },
+    Err(failure) => {
+      println!("Day {} Part {} disagrees with the oracle, shrunk to:\n{}", options.day, failure.part, failure.input);
+      println!("Expected: {}", failure.expected);
+      println!("Actual:   {}", failure.actual);
+      println!("Replay with: fuzz --day {} --part {} --seed {} --iterations 1", options.day, failure.part, failure.seed);
+      process::exit(1);
+    }
+  }
+}
+
+/// Parses the day's input and answers queries about it from stdin.
+fn repl(options: &RunOptions) {
+  let Some(day) = options.days.single() else {
+    return;
+  };
+
+  let Some((_, inspector)) = repl::INSPECTORS.iter().find(|(inspected, _)| *inspected == day) else {
+    eprintln!("Day {} has no queries", day);
+    process::exit(2);
+  };
+
+  let result = InputResolver::current().read(day, Some(&options.input_for(day)))
+    .map_err(|error| error.to_string())
+    .and_then(|(_, contents)| inspector(&contents, &mut io::stdin().lock(), &mut io::stdout()));
+
+  if let Err(message) = result {
+    eprintln!("{}", message);
+    process::exit(1);
+  }
+}
+
+fn new_day(day: u8) {
+  match Scaffold::default().new_day(day) {
+    Ok(paths) => {
+      for path in paths {
+        println!("Wrote {}", path);
+      }
+      println!("Paste the puzzle input and example into the new input files, then add the example answers to src/examples.rs");
+    },
+    Err(message) => {
+      eprintln!("{}", message);
+      process::exit(1);
+    }
+  }
+}
+
+fn load_answers() -> AnswerStore {
+  match AnswerStore::load(answers::answers_file()) {
+    Ok(store) => store,
+    Err(message) => {
+      eprintln!("{}", message);
+      process::exit(2);
+    }
+  }
+}
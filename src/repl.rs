@@ -0,0 +1,117 @@
+use std::io::{BufRead, Write};
+use std::str::FromStr;
+
+use crate::day3::Day3;
+use crate::day4::Day4;
+use crate::day5::Day5;
+use crate::day8::Day8;
+use crate::solution::{self, Solution};
+
+/// A day whose parsed input can be queried from `repl`.
+pub trait Inspect: Solution {
+  /// Usage and a short description of each query, listed by `help`.
+  const QUERIES: &'static [&'static str];
+
+  /// Answers `query` with its arguments, e.g. `node` and `AAA`. Unknown queries and bad
+  /// arguments are `Err` with a message for the user.
+  fn query(parsed: &Self::Parsed<'_>, query: &str, args: &str) -> Result<String, String>;
+}
+
+pub type Inspector = fn(&str, &mut dyn BufRead, &mut dyn Write) -> Result<(), String>;
+
+/// Every day with an `Inspect` implementation.
+pub const INSPECTORS: &[(u8, Inspector)] = &[
+  (3, session::<Day3>),
+  (4, session::<Day4>),
+  (5, session::<Day5>),
+  (8, session::<Day8>),
+];
+
+const BUILTINS: &[&str] = &[
+  "part <1|2>          solve a part against the parsed input",
+  "help                list the queries",
+  "quit                leave the repl",
+];
+
+/// Parses `contents` once, then answers queries read from `input` until it ends or `quit`.
+pub fn session<S: Inspect>(contents: &str, input: &mut dyn BufRead, output: &mut dyn Write) -> Result<(), String> {
+  let parsed = S::parse(contents).map_err(|error| error.to_string())?;
+
+  let write_error = |error: std::io::Error| format!("Could not write to the terminal: {}", error);
+
+  writeln!(output, "Parsed day {}, type 'help' for queries", S::DAY).map_err(write_error)?;
+
+  loop {
+    write!(output, "day{}> ", S::DAY).and_then(|_| output.flush()).map_err(write_error)?;
+
+    let mut line = String::new();
+    if input.read_line(&mut line).map_err(|error| format!("Could not read a query: {}", error))? == 0 {
+      // End the prompt's line when the input runs out, e.g. on Ctrl-D
+      return writeln!(output).map_err(write_error);
+    }
+
+    let line = line.trim();
+    let (query, args) = line.split_once(' ').map(|(query, args)| (query, args.trim())).unwrap_or((line, ""));
+
+    let response = match query {
+      "" => continue,
+      "quit" | "exit" => return Ok(()),
+      "help" => Ok(S::QUERIES.iter().chain(BUILTINS).map(|usage| format!("  {}", usage)).collect::<Vec<String>>().join("\n")),
+      "part" => match args {
+        "1" | "2" => solution::solve::<S>(&parsed, number(args)?).map(|answer| answer.to_string()).map_err(|error| error.message),
+        _ => Err("Usage: part <1|2>".to_string()),
+      },
+      _ => S::query(&parsed, query, args),
+    };
+
+    match response {
+      Ok(response) => writeln!(output, "{}", response),
+      Err(message) => writeln!(output, "error: {}", message),
+    }.map_err(write_error)?;
+  }
+}
+
+/// Parses a query argument, naming it in the error.
+pub fn number<T: FromStr>(text: &str) -> Result<T, String> {
+  text.trim().parse().map_err(|_| format!("Expected a number, got '{}'", text))
+}
+
+/// The message for a query the day doesn't know.
+pub fn unknown(query: &str) -> String {
+  format!("Unknown query '{}', type 'help' for the list", query)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn run<S: Inspect>(contents: &str, queries: &str) -> String {
+    let mut output = vec![];
+    session::<S>(contents, &mut queries.as_bytes(), &mut output).unwrap();
+    String::from_utf8(output).unwrap()
+  }
+
+  #[test]
+  fn queries_are_answered_until_quit() {
+    let network = "LR\n\nAAA = (BBB, ZZZ)\nBBB = (AAA, ZZZ)\nZZZ = (ZZZ, ZZZ)\n";
+    let output = run::<Day8>(network, "node BBB\n\npart 1\nnode XXX\nhover\nquit\nnode AAA\n");
+
+    let responses: Vec<&str> = output.split("day8> ").skip(1).map(|response| response.trim_end()).collect();
+
+    assert!(output.starts_with("Parsed day 8"));
+    assert_eq!(responses[0], "BBB = (AAA, ZZZ), reached from AAA");
+    assert_eq!(responses[1], "");
+    assert_eq!(responses[2], "2");
+    assert_eq!(responses[3], "error: No node 'XXX'");
+    assert_eq!(responses[4], "error: Unknown query 'hover', type 'help' for the list");
+    assert_eq!(responses.len(), 6);
+  }
+
+  #[test]
+  fn help_lists_the_day_queries_and_builtins() {
+    let output = run::<Day4>("Card 1: 41 48 | 83 86\n", "help\n");
+
+    assert!(output.contains("  card <n>"));
+    assert!(output.contains("  part <1|2>"));
+  }
+}
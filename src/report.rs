@@ -0,0 +1,137 @@
+use std::str::FromStr;
+
+use crate::solution::{Answer, TimedRun};
+use crate::utils::json::JsonValue;
+
+/// How the runner prints its results.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+  /// `Day N Part M: answer` lines for reading in a terminal.
+  #[default]
+  Text,
+  Json,
+  Csv,
+}
+
+impl FromStr for OutputFormat {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s {
+      "text" => Ok(OutputFormat::Text),
+      "json" => Ok(OutputFormat::Json),
+      "csv" => Ok(OutputFormat::Csv),
+      _ => Err(format!("Format must be text, json or csv, got '{}'", s)),
+    }
+  }
+}
+
+/// A part that ran to completion, as it appears in a report.
+pub struct PartResult {
+  pub day: u8,
+  pub part: u8,
+  pub run: TimedRun,
+}
+
+const CSV_HEADER: &str = "day,part,answer,input,input_hash,parse_ns,solve_ns";
+
+/// Renders the results as a JSON document, one object per part under `results`.
+/// Numeric answers stay numbers, unsolved parts have a `null` answer.
+pub fn to_json(results: &[PartResult]) -> String {
+  let results = results.iter().map(|result| {
+    JsonValue::Object(vec![
+      ("day".to_string(), result.day.into()),
+      ("part".to_string(), result.part.into()),
+      ("answer".to_string(), (&result.run.answer).into()),
+      ("input".to_string(), result.run.input.as_str().into()),
+      ("input_hash".to_string(), result.run.input_hash.as_str().into()),
+      ("parse_ns".to_string(), (result.run.parse.as_nanos() as u64).into()),
+      ("solve_ns".to_string(), (result.run.solve.as_nanos() as u64).into()),
+    ])
+  }).collect();
+
+  JsonValue::Object(vec![("results".to_string(), JsonValue::Array(results))]).to_string()
+}
+
+/// Renders the results as CSV with a header row. Unsolved parts have an empty answer.
+pub fn to_csv(results: &[PartResult]) -> String {
+  let mut csv = format!("{}\n", CSV_HEADER);
+
+  for result in results {
+    let answer = match &result.run.answer {
+      Answer::Unsolved => String::new(),
+      answer => answer.to_string(),
+    };
+
+    let row = [
+      result.day.to_string(),
+      result.part.to_string(),
+      csv_field(&answer),
+      csv_field(&result.run.input),
+      result.run.input_hash.clone(),
+      result.run.parse.as_nanos().to_string(),
+      result.run.solve.as_nanos().to_string(),
+    ];
+
+    csv.push_str(&row.join(","));
+    csv.push('\n');
+  }
+
+  csv
+}
+
+/// Quotes a field if it holds a delimiter, quote or line break, doubling any quotes inside.
+fn csv_field(value: &str) -> String {
+  if !value.contains([',', '"', '\n', '\r']) {
+    return value.to_string();
+  }
+
+  format!("\"{}\"", value.replace('"', "\"\""))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::time::Duration;
+
+  fn results() -> Vec<PartResult> {
+    let run = |answer: Answer| TimedRun {
+      answer,
+      input: "./inputs/day1part1.txt".to_string(),
+      input_hash: "85944171f73967e8".to_string(),
+      parse: Duration::from_nanos(1_500),
+      solve: Duration::from_micros(20),
+      trace: vec![],
+      parse_alloc: None,
+      solve_alloc: None,
+    };
+
+    vec![
+      PartResult { day: 1, part: 1, run: run(Answer::Number(55607)) },
+      PartResult { day: 1, part: 2, run: run(Answer::Text("A, \"B\"".to_string())) },
+      PartResult { day: 3, part: 2, run: run(Answer::Unsolved) },
+    ]
+  }
+
+  #[test]
+  fn json_keeps_answer_types() {
+    let json: JsonValue = to_json(&results()).parse().unwrap();
+    let results = json.get("results").and_then(|results| results.as_array()).unwrap();
+
+    assert_eq!(results[0].get("answer"), Some(&JsonValue::Integer(55607)));
+    assert_eq!(results[1].get("answer").and_then(|answer| answer.as_str()), Some("A, \"B\""));
+    assert_eq!(results[2].get("answer"), Some(&JsonValue::Null));
+    assert_eq!(results[0].get("input_hash").and_then(|hash| hash.as_str()), Some("85944171f73967e8"));
+    assert_eq!(results[0].get("solve_ns").and_then(|solve| solve.as_i64()), Some(20_000));
+  }
+
+  #[test]
+  fn csv_quotes_awkward_fields() {
+    assert_eq!(to_csv(&results()), "\
+day,part,answer,input,input_hash,parse_ns,solve_ns
+1,1,55607,./inputs/day1part1.txt,85944171f73967e8,1500,20000
+1,2,\"A, \"\"B\"\"\",./inputs/day1part1.txt,85944171f73967e8,1500,20000
+3,2,,./inputs/day1part1.txt,85944171f73967e8,1500,20000
+");
+  }
+}
@@ -0,0 +1,166 @@
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+
+use crate::utils::input_resolver::{InputKind, InputResolver};
+
+/// The starting point for a new day. `{day}` is replaced with the day number.
+const DAY_TEMPLATE: &str = "\
+use crate::solution::{self, Answer, Solution};
+use crate::utils::error::AocError;
+
+pub struct Day{day};
+
+impl Solution for Day{day} {
+  const DAY: u8 = {day};
+
+  type Parsed<'a> = Vec<&'a str>;
+
+  fn parse(input: &str) -> Result<Self::Parsed<'_>, AocError> {
+    Ok(input.lines().collect())
+  }
+
+  fn part_1(_lines: &Self::Parsed<'_>) -> Result<Answer, AocError> {
+    Ok(Answer::Unsolved)
+  }
+
+  fn part_2(_lines: &Self::Parsed<'_>) -> Result<Answer, AocError> {
+    Ok(Answer::Unsolved)
+  }
+}
+
+pub fn part_1(inputFile: Option<&str>) -> Result<Answer, AocError> {
+  solution::run_part_1::<Day{day}>(inputFile)
+}
+
+pub fn part_2(inputFile: Option<&str>) -> Result<Answer, AocError> {
+  solution::run_part_2::<Day{day}>(inputFile)
+}
+";
+
+const REGISTRY_START: &str = "register_days! {";
+
+/// Where a new day's files go.
+pub struct Scaffold {
+  pub source_dir: String,
+  pub input_dir: String,
+}
+
+impl Default for Scaffold {
+  fn default() -> Self {
+    Scaffold { source_dir: "./src".to_string(), input_dir: InputResolver::current().directory().to_string() }
+  }
+}
+
+impl Scaffold {
+  /// Writes the day's module and empty inputs, then registers the module in `main.rs`.
+  /// Nothing is written if any of the files already exist or the day is already registered.
+  /// Returns the paths that were created or changed.
+  pub fn new_day(self: &Self, day: u8) -> Result<Vec<String>, String> {
+    let resolver = InputResolver::new(&self.input_dir);
+
+    let module = format!("{}/day{}.rs", self.source_dir, day);
+    let inputs = [resolver.path(day, &InputKind::Real), resolver.path(day, &InputKind::Example)];
+    let main = format!("{}/main.rs", self.source_dir);
+
+    let existing: Vec<&String> = [&module].into_iter().chain(inputs.iter()).filter(|path| Path::new(path).exists()).collect();
+    if !existing.is_empty() {
+      let existing: Vec<&str> = existing.iter().map(|path| path.as_str()).collect();
+      return Err(format!("Refusing to overwrite {}", existing.join(", ")));
+    }
+
+    let mainSource = fs::read_to_string(&main).map_err(|error| format!("Could not read {}: {}", main, error))?;
+    let mainSource = register_day(&mainSource, day)?;
+
+    fs::create_dir_all(&self.input_dir).map_err(|error| format!("Could not create {}: {}", self.input_dir, error))?;
+
+    create_new(&module, &DAY_TEMPLATE.replace("{day}", &day.to_string()))?;
+    for input in inputs.iter() {
+      create_new(input, "")?;
+    }
+
+    fs::write(&main, mainSource).map_err(|error| format!("Could not write {}: {}", main, error))?;
+
+    Ok([module].into_iter().chain(inputs).chain([main]).collect())
+  }
+}
+
+/// Creates `path`, failing rather than truncating if something appeared there since it was checked.
+fn create_new(path: &str, contents: &str) -> Result<(), String> {
+  OpenOptions::new()
+    .write(true)
+    .create_new(true)
+    .open(path)
+    .and_then(|mut file| file.write_all(contents.as_bytes()))
+    .map_err(|error| format!("Could not create {}: {}", path, error))
+}
+
+/// Adds `dayN => DayN,` to the `register_days!` list in `mainSource`, keeping the days in order.
+fn register_day(mainSource: &str, day: u8) -> Result<String, String> {
+  let start = mainSource.find(REGISTRY_START)
+    .map(|index| index + REGISTRY_START.len())
+    .ok_or_else(|| format!("Could not find '{}' in main.rs", REGISTRY_START))?;
+  let end = mainSource[start..].find('}')
+    .map(|index| start + index)
+    .ok_or("The register_days! list in main.rs is not closed")?;
+
+  let entry_day = |line: &str| -> Option<u8> {
+    let (module, _) = line.trim().split_once("=>")?;
+    module.trim().strip_prefix("day")?.parse().ok()
+  };
+
+  let mut insert_at = start + mainSource[start..end].find('\n').map(|index| index + 1).unwrap_or(0);
+  let mut offset = start;
+
+  for line in mainSource[start..end].split_inclusive('\n') {
+    match entry_day(line) {
+      Some(existing) if existing == day => return Err(format!("Day {} is already registered in main.rs", day)),
+      Some(existing) if existing < day => insert_at = offset + line.len(),
+      _ => {},
+    }
+    offset += line.len();
+  }
+
+  let mut registered = mainSource.to_string();
+  registered.insert_str(insert_at, &format!("  day{} => Day{},\n", day, day));
+
+  Ok(registered)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const MAIN: &str = "pub mod utils;\n\nregister_days! {\n  day1 => Day1,\n  day3 => Day3,\n}\n\nfn main() {}\n";
+
+  #[test]
+  fn days_are_registered_in_order() {
+    assert_eq!(register_day(MAIN, 2).unwrap(), "pub mod utils;\n\nregister_days! {\n  day1 => Day1,\n  day2 => Day2,\n  day3 => Day3,\n}\n\nfn main() {}\n");
+    assert!(register_day(MAIN, 4).unwrap().contains("  day3 => Day3,\n  day4 => Day4,\n}"));
+    assert_eq!(register_day(MAIN, 3), Err("Day 3 is already registered in main.rs".to_string()));
+  }
+
+  #[test]
+  fn existing_files_are_never_overwritten() {
+    let root = std::env::temp_dir().join(format!("aoc_scaffold_{}", std::process::id()));
+    let scaffold = Scaffold {
+      source_dir: root.join("src").to_string_lossy().to_string(),
+      input_dir: root.join("inputs").to_string_lossy().to_string(),
+    };
+
+    fs::create_dir_all(&scaffold.source_dir).unwrap();
+    fs::write(format!("{}/main.rs", scaffold.source_dir), MAIN).unwrap();
+
+    let created = scaffold.new_day(9);
+    let second = scaffold.new_day(9);
+    let module = fs::read_to_string(format!("{}/day9.rs", scaffold.source_dir)).unwrap();
+    let main = fs::read_to_string(format!("{}/main.rs", scaffold.source_dir)).unwrap();
+
+    fs::remove_dir_all(&root).unwrap();
+
+    assert_eq!(created.unwrap().len(), 4);
+    assert!(second.unwrap_err().starts_with("Refusing to overwrite"));
+    assert!(module.contains("impl Solution for Day9 {\n  const DAY: u8 = 9;"));
+    assert!(main.contains("  day3 => Day3,\n  day9 => Day9,\n}"));
+  }
+}
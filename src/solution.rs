@@ -0,0 +1,200 @@
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use crate::utils::alloc::{self, AllocStats};
+use crate::utils::error::AocError;
+use crate::utils::hash;
+use crate::utils::input_resolver::InputResolver;
+use crate::utils::json::JsonValue;
+use crate::utils::trace::{self, TraceSpan};
+
+/// The result of running a single part, printed by the runner.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+  Number(i64),
+  Text(String),
+  Unsolved,
+}
+
+impl fmt::Display for Answer {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Answer::Number(number) => write!(f, "{}", number),
+      Answer::Text(text) => write!(f, "{}", text),
+      Answer::Unsolved => write!(f, "unsolved"),
+    }
+  }
+}
+
+impl From<i32> for Answer {
+  fn from(value: i32) -> Self {
+    Answer::Number(value as i64)
+  }
+}
+
+impl From<u32> for Answer {
+  fn from(value: u32) -> Self {
+    Answer::Number(value as i64)
+  }
+}
+
+impl From<i64> for Answer {
+  fn from(value: i64) -> Self {
+    Answer::Number(value)
+  }
+}
+
+impl From<u64> for Answer {
+  fn from(value: u64) -> Self {
+    Answer::Number(i64::try_from(value).expect("Answer should fit in an i64"))
+  }
+}
+
+impl From<usize> for Answer {
+  fn from(value: usize) -> Self {
+    Answer::Number(i64::try_from(value).expect("Answer should fit in an i64"))
+  }
+}
+
+impl From<String> for Answer {
+  fn from(value: String) -> Self {
+    Answer::Text(value)
+  }
+}
+
+impl From<&str> for Answer {
+  fn from(value: &str) -> Self {
+    Answer::Text(value.to_string())
+  }
+}
+
+/// Numeric answers stay JSON numbers, unsolved parts are `null`.
+impl From<&Answer> for JsonValue {
+  fn from(answer: &Answer) -> Self {
+    match answer {
+      Answer::Number(number) => JsonValue::Integer(*number),
+      Answer::Text(text) => text.as_str().into(),
+      Answer::Unsolved => JsonValue::Null,
+    }
+  }
+}
+
+impl Answer {
+  pub fn from_json(value: &JsonValue) -> Option<Self> {
+    match value {
+      JsonValue::Integer(number) => Some(Answer::Number(*number)),
+      JsonValue::String(text) => Some(Answer::Text(text.clone())),
+      JsonValue::Null => Some(Answer::Unsolved),
+      _ => None,
+    }
+  }
+}
+
+/// A single day's puzzle. `Parsed` may borrow from the input buffer, which the runner reads
+/// once and keeps alive while the parts run.
+pub trait Solution {
+  const DAY: u8;
+
+  type Parsed<'a>;
+
+  fn parse(input: &str) -> Result<Self::Parsed<'_>, AocError>;
+
+  /// Parts can fail on inputs `parse` couldn't rule out, e.g. a line part 2 can read but part 1 can't.
+  fn part_1(parsed: &Self::Parsed<'_>) -> Result<Answer, AocError>;
+
+  fn part_2(parsed: &Self::Parsed<'_>) -> Result<Answer, AocError>;
+}
+
+/// Reads the input file, returning its path alongside the contents for error reporting.
+fn read_contents(day: u8, inputFile: Option<&str>) -> Result<(String, String), AocError> {
+  InputResolver::current().read(day, inputFile)
+}
+
+pub fn solve<S: Solution>(parsed: &S::Parsed<'_>, part: u8) -> Result<Answer, AocError> {
+  match part {
+    1 => S::part_1(parsed),
+    _ => S::part_2(parsed),
+  }
+}
+
+fn run_part<S: Solution>(inputFile: Option<&str>, part: u8) -> Result<Answer, AocError> {
+  let (inputFile, contents) = read_contents(S::DAY, inputFile)?;
+  let parsed = S::parse(&contents).map_err(|error| error.in_file(&inputFile))?;
+
+  solve::<S>(&parsed, part).map_err(|error| error.in_file(&inputFile))
+}
+
+pub fn run_part_1<S: Solution>(inputFile: Option<&str>) -> Result<Answer, AocError> {
+  run_part::<S>(inputFile, 1)
+}
+
+pub fn run_part_2<S: Solution>(inputFile: Option<&str>) -> Result<Answer, AocError> {
+  run_part::<S>(inputFile, 2)
+}
+
+/// A part's answer along with how long parsing and solving took. Reading the file is not timed.
+pub struct TimedRun {
+  pub answer: Answer,
+  /// The path that was read, `<stdin>` for piped input.
+  pub input: String,
+  pub input_hash: String,
+  /// Parsing is shared by every part in the same `run_timed` call, so they all report the same time.
+  pub parse: Duration,
+  pub solve: Duration,
+  /// The `parse` and `solve` span trees when tracing is enabled, empty otherwise.
+  pub trace: Vec<TraceSpan>,
+  /// What parsing and solving allocated, only counted with the `alloc-stats` feature.
+  pub parse_alloc: Option<AllocStats>,
+  pub solve_alloc: Option<AllocStats>,
+}
+
+/// Reads and parses the input once, then runs each of `parts` against it in order.
+pub fn run_timed<S: Solution>(inputFile: Option<&str>, parts: &[u8]) -> Result<Vec<TimedRun>, AocError> {
+  let (inputFile, contents) = read_contents(S::DAY, inputFile)?;
+  let input_hash = hash::input_hash(&contents);
+
+  let start = Instant::now();
+  let ((parsed, parse_alloc), parseTrace) = trace::record("parse", || alloc::measure(|| S::parse(&contents)));
+  let parsed = parsed.map_err(|error| error.in_file(&inputFile))?;
+  let parse = start.elapsed();
+
+  let runs = parts.iter().map(|part| {
+    let start = Instant::now();
+    let ((answer, solve_alloc), solveTrace) = trace::record("solve", || alloc::measure(|| solve::<S>(&parsed, *part)));
+    let solve = start.elapsed();
+    let answer = answer.map_err(|error| error.in_file(&inputFile))?;
+
+    let trace = parseTrace.iter().cloned().chain(solveTrace).collect();
+
+    Ok(TimedRun { answer, input: inputFile.clone(), input_hash: input_hash.clone(), parse, solve, trace, parse_alloc, solve_alloc })
+  }).collect();
+
+  runs
+}
+
+pub type TimedRunner = fn(Option<&str>, &[u8]) -> Result<Vec<TimedRun>, AocError>;
+
+/// An entry in the day registry, pointing at a day's `part_1`/`part_2` entry points.
+pub struct RegisteredDay {
+  pub day: u8,
+  pub part_1: fn(Option<&str>) -> Result<Answer, AocError>,
+  pub part_2: fn(Option<&str>) -> Result<Answer, AocError>,
+  pub timed: TimedRunner,
+}
+
+/// Declares every day module and builds the `REGISTRY` the runner loops over.
+/// Each day module must expose `part_1`/`part_2` entry points and a `Solution` type.
+macro_rules! register_days {
+  ($($module:ident => $solution:ident),* $(,)?) => {
+    $(pub mod $module;)*
+
+    pub const REGISTRY: &[solution::RegisteredDay] = &[
+      $(solution::RegisteredDay {
+        day: <$module::$solution as solution::Solution>::DAY,
+        part_1: $module::part_1,
+        part_2: $module::part_2,
+        timed: solution::run_timed::<$module::$solution>,
+      },)*
+    ];
+  };
+}
@@ -0,0 +1,400 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::config::{Config, YEAR};
+use crate::fetch::USER_AGENT;
+use crate::solution::Answer;
+use crate::utils::http::{self, Url};
+use crate::utils::input_resolver::InputResolver;
+use crate::utils::json::JsonValue;
+
+/// Every submission is logged next to the inputs and answers.
+pub fn history_file() -> String {
+  format!("{}/submissions.json", InputResolver::current().directory())
+}
+
+/// What the site said about a submitted answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+  Correct,
+  TooHigh,
+  TooLow,
+  Wrong,
+  /// Submitted too soon after the previous attempt, with how long is left to wait.
+  RateLimited(Duration),
+  /// The site isn't expecting an answer for this part: it is already solved, or it is part 2
+  /// and part 1 isn't solved yet. The site says the same thing for both, so this doesn't block
+  /// submitting again.
+  WrongLevel,
+}
+
+impl Outcome {
+  /// Reads the outcome out of the response page.
+  pub fn from_response(body: &str) -> Option<Self> {
+    if body.contains("That's the right answer") {
+      return Some(Outcome::Correct);
+    }
+
+    if body.contains("You gave an answer too recently") {
+      let wait = body.split("You have ").nth(1)
+        .and_then(|rest| rest.split(" left to wait").next())
+        .and_then(parse_wait)
+        .unwrap_or_default();
+      return Some(Outcome::RateLimited(wait));
+    }
+
+    if body.contains("your answer is too high") {
+      return Some(Outcome::TooHigh);
+    }
+
+    if body.contains("your answer is too low") {
+      return Some(Outcome::TooLow);
+    }
+
+    if body.contains("That's not the right answer") {
+      return Some(Outcome::Wrong);
+    }
+
+    if body.contains("You don't seem to be solving the right level") {
+      return Some(Outcome::WrongLevel);
+    }
+
+    None
+  }
+
+  fn name(self: &Self) -> &'static str {
+    match self {
+      Outcome::Correct => "correct",
+      Outcome::TooHigh => "too_high",
+      Outcome::TooLow => "too_low",
+      Outcome::Wrong => "wrong",
+      Outcome::RateLimited(_) => "rate_limited",
+      Outcome::WrongLevel => "wrong_level",
+    }
+  }
+
+  /// Whether the answer itself is known to be wrong.
+  fn is_wrong(self: &Self) -> bool {
+    matches!(self, Outcome::TooHigh | Outcome::TooLow | Outcome::Wrong)
+  }
+}
+
+/// Parses waits like `1m 23s` or `45s`.
+fn parse_wait(text: &str) -> Option<Duration> {
+  let mut seconds = 0;
+
+  for part in text.split_whitespace() {
+    let (value, unit) = part.split_at(part.len().checked_sub(1)?);
+    let value = u64::from_str(value).ok()?;
+
+    seconds += match unit {
+      "h" => value * 3600,
+      "m" => value * 60,
+      "s" => value,
+      _ => return None,
+    };
+  }
+
+  Some(Duration::from_secs(seconds))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Attempt {
+  pub day: u8,
+  pub part: u8,
+  pub answer: Answer,
+  pub outcome: Outcome,
+  /// Seconds since the Unix epoch.
+  pub timestamp: u64,
+}
+
+/// Every answer submitted so far, stored as JSON.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct History {
+  pub attempts: Vec<Attempt>,
+}
+
+impl History {
+  pub fn load<P>(filename: P) -> Result<Self, String>
+  where P: AsRef<Path>, {
+    let contents = match fs::read_to_string(&filename) {
+      Ok(contents) => contents,
+      Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+      Err(error) => return Err(format!("Could not read {}: {}", filename.as_ref().display(), error)),
+    };
+
+    let invalid = || format!("{} is not a valid submission history", filename.as_ref().display());
+
+    let json = JsonValue::from_str(&contents).map_err(|error| format!("{}: {}", invalid(), error))?;
+    let attempts = json.get("attempts").and_then(|attempts| attempts.as_array()).ok_or_else(invalid)?;
+
+    let mut history = History::default();
+
+    for attempt in attempts {
+      let field = |name: &str| attempt.get(name).and_then(|value| value.as_i64()).ok_or_else(invalid);
+
+      let outcome = match attempt.get("outcome").and_then(|outcome| outcome.as_str()) {
+        Some("correct") => Outcome::Correct,
+        Some("too_high") => Outcome::TooHigh,
+        Some("too_low") => Outcome::TooLow,
+        Some("wrong") => Outcome::Wrong,
+        Some("rate_limited") => Outcome::RateLimited(Duration::from_secs(field("wait_seconds")? as u64)),
+        // Older histories logged every wrong level as already solved
+        Some("wrong_level") | Some("already_solved") => Outcome::WrongLevel,
+        _ => return Err(invalid()),
+      };
+
+      history.attempts.push(Attempt {
+        day: field("day")? as u8,
+        part: field("part")? as u8,
+        answer: attempt.get("answer").and_then(Answer::from_json).ok_or_else(invalid)?,
+        outcome,
+        timestamp: field("timestamp")? as u64,
+      });
+    }
+
+    Ok(history)
+  }
+
+  pub fn save<P>(self: &Self, filename: P) -> io::Result<()>
+  where P: AsRef<Path>, {
+    let attempts = self.attempts.iter().map(|attempt| {
+      let mut fields = vec![
+        ("day".to_string(), attempt.day.into()),
+        ("part".to_string(), attempt.part.into()),
+        ("answer".to_string(), (&attempt.answer).into()),
+        ("outcome".to_string(), attempt.outcome.name().into()),
+        ("timestamp".to_string(), attempt.timestamp.into()),
+      ];
+
+      if let Outcome::RateLimited(wait) = attempt.outcome {
+        fields.push(("wait_seconds".to_string(), wait.as_secs().into()));
+      }
+
+      JsonValue::Object(fields)
+    }).collect();
+
+    let json = JsonValue::Object(vec![("attempts".to_string(), JsonValue::Array(attempts))]);
+
+    fs::write(filename, format!("{}\n", json))
+  }
+
+  /// Refuses answers the history already rules out: a solved part, a known wrong answer,
+  /// one outside the too-high/too-low bounds, or a submission while still rate-limited.
+  pub fn check(self: &Self, day: u8, part: u8, answer: &Answer, now: u64) -> Result<(), String> {
+    if *answer == Answer::Unsolved {
+      return Err(format!("Day {} Part {} is unsolved, there is nothing to submit", day, part));
+    }
+
+    let attempts: Vec<&Attempt> = self.attempts.iter().filter(|attempt| attempt.day == day && attempt.part == part).collect();
+
+    if let Some(correct) = attempts.iter().find(|attempt| attempt.outcome == Outcome::Correct) {
+      return Err(format!("Day {} Part {} is already solved ({})", day, part, correct.answer));
+    }
+
+    if let Some(wrong) = attempts.iter().find(|attempt| attempt.outcome.is_wrong() && attempt.answer == *answer) {
+      return Err(format!("{} was already submitted and was {}", answer, wrong.outcome.name().replace('_', " ")));
+    }
+
+    if let Answer::Number(value) = answer {
+      let bound = |outcome: Outcome| attempts.iter()
+        .filter(move |attempt| attempt.outcome == outcome)
+        .filter_map(|attempt| match attempt.answer { Answer::Number(number) => Some(number), _ => None });
+
+      if let Some(high) = bound(Outcome::TooHigh).min().filter(|high| value >= high) {
+        return Err(format!("{} can't be right, {} was already too high", value, high));
+      }
+
+      if let Some(low) = bound(Outcome::TooLow).max().filter(|low| value <= low) {
+        return Err(format!("{} can't be right, {} was already too low", value, low));
+      }
+    }
+
+    let limited_until = self.attempts.iter()
+      .filter_map(|attempt| match attempt.outcome {
+        Outcome::RateLimited(wait) => Some(attempt.timestamp + wait.as_secs()),
+        _ => None,
+      })
+      .max();
+
+    if let Some(until) = limited_until.filter(|until| *until > now) {
+      return Err(format!("Still rate-limited, wait another {}s", until - now));
+    }
+
+    Ok(())
+  }
+}
+
+pub fn now() -> u64 {
+  SystemTime::now().duration_since(UNIX_EPOCH).map(|elapsed| elapsed.as_secs()).unwrap_or(0)
+}
+
+/// Checks `answer` against the history, posts it and logs the attempt.
+pub fn submit(day: u8, part: u8, answer: &Answer, config: &Config, historyFile: &str) -> Result<Outcome, String> {
+  let mut history = History::load(historyFile)?;
+
+  history.check(day, part, answer, now())?;
+
+  let url = Url::parse(config.base_url()?)?.join(&format!("{}/day/{}/answer", YEAR, day));
+  let cookie = format!("session={}", config.session()?);
+  let body = format!("level={}&answer={}", part, form_encode(&answer.to_string()));
+
+  let headers = [
+    ("Cookie", cookie.as_str()),
+    ("User-Agent", USER_AGENT),
+    ("Content-Type", "application/x-www-form-urlencoded"),
+  ];
+
+  let response = http::request("POST", &url, &headers, Some(&body))?;
+
+  if response.status != 200 {
+    return Err(format!("Unexpected HTTP {} submitting day {} part {}", response.status, day, part));
+  }
+
+  let outcome = Outcome::from_response(&response.body)
+    .ok_or("Could not tell whether the answer was right from the response")?;
+
+  history.attempts.push(Attempt { day, part, answer: answer.clone(), outcome, timestamp: now() });
+  history.save(historyFile).map_err(|error| format!("Could not write {}: {}", historyFile, error))?;
+
+  Ok(outcome)
+}
+
+fn form_encode(value: &str) -> String {
+  value.bytes().map(|byte| match byte {
+    b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (byte as char).to_string(),
+    _ => format!("%{:02X}", byte),
+  }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::io::{Read, Write};
+  use std::net::TcpListener;
+  use std::thread;
+
+  fn attempt(answer: i64, outcome: Outcome, timestamp: u64) -> Attempt {
+    Attempt { day: 5, part: 1, answer: Answer::Number(answer), outcome, timestamp }
+  }
+
+  #[test]
+  fn responses_are_recognised() {
+    assert_eq!(Outcome::from_response("<p>That's the right answer! You are one gold star closer.</p>"), Some(Outcome::Correct));
+    assert_eq!(Outcome::from_response("<p>That's not the right answer; your answer is too high.</p>"), Some(Outcome::TooHigh));
+    assert_eq!(Outcome::from_response("<p>That's not the right answer; your answer is too low.</p>"), Some(Outcome::TooLow));
+    assert_eq!(Outcome::from_response("<p>That's not the right answer. If you're stuck...</p>"), Some(Outcome::Wrong));
+    assert_eq!(
+      Outcome::from_response("<p>You gave an answer too recently. You have 1m 23s left to wait.</p>"),
+      Some(Outcome::RateLimited(Duration::from_secs(83)))
+    );
+    assert_eq!(Outcome::from_response("<p>You don't seem to be solving the right level.</p>"), Some(Outcome::WrongLevel));
+    assert_eq!(Outcome::from_response("<html></html>"), None);
+  }
+
+  #[test]
+  fn history_rules_out_known_bad_answers() {
+    let history = History {
+      attempts: vec![
+        attempt(500, Outcome::TooHigh, 100),
+        attempt(200, Outcome::TooLow, 200),
+        attempt(300, Outcome::Wrong, 300),
+        attempt(301, Outcome::RateLimited(Duration::from_secs(60)), 400),
+      ],
+    };
+
+    assert!(history.check(5, 1, &Answer::Number(300), 1_000).unwrap_err().contains("already submitted"));
+    assert!(history.check(5, 1, &Answer::Number(600), 1_000).unwrap_err().contains("too high"));
+    assert!(history.check(5, 1, &Answer::Number(200), 1_000).unwrap_err().contains("too low"));
+    assert!(history.check(5, 1, &Answer::Number(350), 430).unwrap_err().contains("rate-limited"));
+    assert_eq!(history.check(5, 1, &Answer::Number(350), 1_000), Ok(()));
+    assert_eq!(history.check(5, 2, &Answer::Number(600), 1_000), Ok(()));
+
+    let early = History { attempts: vec![Attempt { part: 2, ..attempt(42, Outcome::WrongLevel, 100) }] };
+    assert_eq!(early.check(5, 2, &Answer::Number(42), 1_000), Ok(()));
+  }
+
+  #[test]
+  fn history_round_trips_through_json() {
+    let path = std::env::temp_dir().join(format!("aoc_submissions_{}.json", std::process::id()));
+
+    let history = History {
+      attempts: vec![
+        attempt(500, Outcome::TooHigh, 100),
+        Attempt { day: 9, part: 2, answer: Answer::Text("ABC".to_string()), outcome: Outcome::RateLimited(Duration::from_secs(30)), timestamp: 5 },
+      ],
+    };
+    history.save(&path).unwrap();
+
+    let loaded = History::load(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(loaded, history);
+  }
+
+  /// Answers one request per page in `pages`, returning the form body of each request it got.
+  fn mock_site(pages: Vec<&'static str>) -> (String, thread::JoinHandle<Vec<String>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let base_url = format!("http://127.0.0.1:{}", listener.local_addr().unwrap().port());
+
+    let server = thread::spawn(move || pages.into_iter().map(|page| {
+      let (mut stream, _) = listener.accept().unwrap();
+      let mut request = vec![];
+      let mut buffer = [0; 1024];
+
+      // Reads the headers, then as much body as they announce
+      loop {
+        let read = stream.read(&mut buffer).unwrap();
+        assert!(read > 0, "The request ended before its body");
+        request.extend_from_slice(&buffer[..read]);
+
+        let text = String::from_utf8_lossy(&request).to_string();
+        if let Some((head, body)) = text.split_once("\r\n\r\n") {
+          let length = head.lines().find_map(|line| line.strip_prefix("Content-Length: ")).map(|length| length.parse::<usize>().unwrap()).unwrap_or(0);
+          if body.len() >= length {
+            stream.write_all(format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n{}", page.len(), page).as_bytes()).unwrap();
+            return body.to_string();
+          }
+        }
+      }
+    }).collect());
+
+    (base_url, server)
+  }
+
+  #[test]
+  fn submissions_are_posted_and_logged_with_each_verdict() {
+    let (base_url, server) = mock_site(vec![
+      "<p>That's not the right answer; your answer is too high.</p>",
+      "<p>That's not the right answer; your answer is too low.</p>",
+      "<p>That's not the right answer. If you're stuck...</p>",
+      "<p>That's the right answer! You are one gold star closer.</p>",
+      "<p>You gave an answer too recently. You have 45s left to wait.</p>",
+    ]);
+
+    let historyFile = std::env::temp_dir().join(format!("aoc_submit_{}.json", std::process::id())).to_string_lossy().to_string();
+    let config = Config { session: Some("s3cret".to_string()), base_url: Some(base_url) };
+    let submit = |part: u8, answer: i64| submit(5, part, &Answer::Number(answer), &config, &historyFile);
+
+    assert_eq!(submit(1, 500), Ok(Outcome::TooHigh));
+    assert_eq!(submit(1, 100), Ok(Outcome::TooLow));
+    assert_eq!(submit(1, 300), Ok(Outcome::Wrong));
+    assert!(submit(1, 300).unwrap_err().contains("already submitted"));
+    assert_eq!(submit(1, 250), Ok(Outcome::Correct));
+    assert_eq!(submit(2, 7), Ok(Outcome::RateLimited(Duration::from_secs(45))));
+    assert!(submit(2, 8).unwrap_err().contains("rate-limited"));
+
+    let posted = server.join().unwrap();
+    let history = History::load(&historyFile).unwrap();
+    fs::remove_file(&historyFile).unwrap();
+
+    assert_eq!(posted, vec!["level=1&answer=500", "level=1&answer=100", "level=1&answer=300", "level=1&answer=250", "level=2&answer=7"]);
+    assert_eq!(
+      history.attempts.iter().map(|attempt| attempt.outcome).collect::<Vec<Outcome>>(),
+      vec![Outcome::TooHigh, Outcome::TooLow, Outcome::Wrong, Outcome::Correct, Outcome::RateLimited(Duration::from_secs(45))]
+    );
+  }
+}
@@ -0,0 +1,161 @@
+//! Allocation accounting for the runner. With the `alloc-stats` feature a counting allocator
+//! wraps the system one; without it `measure` records nothing.
+
+use std::fmt;
+#[cfg(feature = "alloc-stats")]
+use std::alloc::{GlobalAlloc, Layout, System};
+#[cfg(feature = "alloc-stats")]
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// What one phase of a part allocated. The counters are process wide, so anything running on
+/// another thread at the same time is counted too.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct AllocStats {
+  /// Allocations and reallocations.
+  pub count: u64,
+  pub bytes: u64,
+  /// The most bytes live at once, on top of what was already live when the phase started.
+  pub peak: u64,
+}
+
+impl fmt::Display for AllocStats {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{} allocs, {} allocated, {} peak", self.count, format_bytes(self.bytes), format_bytes(self.peak))
+  }
+}
+
+/// Formats a byte count with a binary unit, e.g. `512B`, `1.50KiB` or `3.20MiB`.
+pub fn format_bytes(bytes: u64) -> String {
+  const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+
+  if bytes < 1024 {
+    return format!("{}B", bytes);
+  }
+
+  let mut value = bytes as f64 / 1024.0;
+  let mut unit = 0;
+
+  while value >= 1024.0 && unit < UNITS.len() - 1 {
+    value /= 1024.0;
+    unit += 1;
+  }
+
+  format!("{:.2}{}", value, UNITS[unit])
+}
+
+pub fn is_enabled() -> bool {
+  cfg!(feature = "alloc-stats")
+}
+
+#[cfg(feature = "alloc-stats")]
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+#[cfg(feature = "alloc-stats")]
+static ALLOCATED: AtomicU64 = AtomicU64::new(0);
+#[cfg(feature = "alloc-stats")]
+static LIVE: AtomicU64 = AtomicU64::new(0);
+#[cfg(feature = "alloc-stats")]
+static PEAK: AtomicU64 = AtomicU64::new(0);
+
+/// Passes everything on to the system allocator, counting as it goes.
+#[cfg(feature = "alloc-stats")]
+pub struct CountingAllocator;
+
+#[cfg(feature = "alloc-stats")]
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+#[cfg(feature = "alloc-stats")]
+impl CountingAllocator {
+  fn allocated(size: usize) {
+    ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+    ALLOCATED.fetch_add(size as u64, Ordering::Relaxed);
+    let live = LIVE.fetch_add(size as u64, Ordering::Relaxed) + size as u64;
+    PEAK.fetch_max(live, Ordering::Relaxed);
+  }
+
+  fn freed(size: usize) {
+    LIVE.fetch_sub(size as u64, Ordering::Relaxed);
+  }
+}
+
+#[cfg(feature = "alloc-stats")]
+unsafe impl GlobalAlloc for CountingAllocator {
+  unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+    let pointer = unsafe { System.alloc(layout) };
+    if !pointer.is_null() {
+      Self::allocated(layout.size());
+    }
+    pointer
+  }
+
+  unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+    let pointer = unsafe { System.alloc_zeroed(layout) };
+    if !pointer.is_null() {
+      Self::allocated(layout.size());
+    }
+    pointer
+  }
+
+  unsafe fn dealloc(&self, pointer: *mut u8, layout: Layout) {
+    unsafe { System.dealloc(pointer, layout) };
+    Self::freed(layout.size());
+  }
+
+  unsafe fn realloc(&self, pointer: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+    let moved = unsafe { System.realloc(pointer, layout, new_size) };
+    if !moved.is_null() {
+      Self::freed(layout.size());
+      Self::allocated(new_size);
+    }
+    moved
+  }
+}
+
+/// Runs `f`, returning what it allocated when the `alloc-stats` feature is on.
+#[cfg(feature = "alloc-stats")]
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, Option<AllocStats>) {
+  let live = LIVE.load(Ordering::Relaxed);
+  PEAK.store(live, Ordering::Relaxed);
+  let count = ALLOCATIONS.load(Ordering::Relaxed);
+  let bytes = ALLOCATED.load(Ordering::Relaxed);
+
+  let result = f();
+
+  let stats = AllocStats {
+    count: ALLOCATIONS.load(Ordering::Relaxed) - count,
+    bytes: ALLOCATED.load(Ordering::Relaxed) - bytes,
+    peak: PEAK.load(Ordering::Relaxed).saturating_sub(live),
+  };
+
+  (result, Some(stats))
+}
+
+/// Runs `f`, returning what it allocated when the `alloc-stats` feature is on.
+#[cfg(not(feature = "alloc-stats"))]
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, Option<AllocStats>) {
+  (f(), None)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn byte_counts_pick_a_binary_unit() {
+    assert_eq!(format_bytes(512), "512B");
+    assert_eq!(format_bytes(1536), "1.50KiB");
+    assert_eq!(format_bytes(3 * 1024 * 1024 + 200 * 1024), "3.20MiB");
+    assert_eq!(AllocStats { count: 3, bytes: 2048, peak: 1024 }.to_string(), "3 allocs, 2.00KiB allocated, 1.00KiB peak");
+  }
+
+  #[test]
+  fn measuring_counts_allocations_only_with_the_feature() {
+    let (vector, stats) = measure(|| vec![0u8; 1 << 20]);
+
+    assert_eq!(vector.len(), 1 << 20);
+    match is_enabled() {
+      true => assert!(stats.is_some_and(|stats| stats.count >= 1 && stats.bytes >= 1 << 20)),
+      false => assert_eq!(stats, None),
+    }
+  }
+}
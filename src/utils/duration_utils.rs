@@ -0,0 +1,33 @@
+use std::time::Duration;
+
+/// Formats a duration with a unit that keeps it readable, e.g. `850ns`, `12.40µs`, `3.21ms` or `1.50s`.
+pub fn format_duration(duration: Duration) -> String {
+  let nanos = duration.as_nanos();
+
+  if nanos < 1_000 {
+    return format!("{}ns", nanos);
+  }
+
+  if nanos < 1_000_000 {
+    return format!("{:.2}µs", nanos as f64 / 1_000.0);
+  }
+
+  if nanos < 1_000_000_000 {
+    return format!("{:.2}ms", nanos as f64 / 1_000_000.0);
+  }
+
+  format!("{:.2}s", duration.as_secs_f64())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn picks_a_readable_unit() {
+    assert_eq!(format_duration(Duration::from_nanos(850)), "850ns");
+    assert_eq!(format_duration(Duration::from_nanos(12_400)), "12.40µs");
+    assert_eq!(format_duration(Duration::from_micros(3_210)), "3.21ms");
+    assert_eq!(format_duration(Duration::from_millis(1_500)), "1.50s");
+  }
+}
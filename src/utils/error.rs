@@ -0,0 +1,192 @@
+use std::fmt;
+use std::io;
+use std::str::FromStr;
+
+use crate::utils::scan;
+
+/// An error found while reading or parsing a puzzle input, pointing at the offending text.
+///
+/// Parsers build errors against the string they were handed; callers that sliced that string
+/// out of a larger input use `within` so the line and column end up relative to the whole file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AocError {
+  pub message: String,
+  pub file: Option<String>,
+  /// 1-based line number, or 0 when the error has no position (e.g. the file could not be read).
+  pub line: usize,
+  /// 1-based column, counted in characters.
+  pub column: usize,
+  /// The offending text itself.
+  pub text: String,
+  /// The full line the offending text sits on, used to render the diagnostic.
+  pub line_text: String,
+}
+
+impl AocError {
+  /// Creates an error for `offending`, which must be a slice of `source` for the position to be found.
+  pub fn new(message: impl Into<String>, source: &str, offending: &str) -> Self {
+    let offset = offset_of(source, offending).unwrap_or(0);
+    let (line, column, line_text) = position_of(source, offset);
+
+    AocError {
+      message: message.into(),
+      file: None,
+      line,
+      column,
+      text: offending.to_string(),
+      line_text: line_text.to_string(),
+    }
+  }
+
+  /// Creates an error pointing just past the end of `source`, for input that stopped too early.
+  pub fn end_of_input(message: impl Into<String>, source: &str) -> Self {
+    Self::new(message, source, &source[source.len()..])
+  }
+
+  pub fn io(file: &str, error: io::Error) -> Self {
+    AocError {
+      message: format!("Could not read input: {}", error),
+      file: Some(file.to_string()),
+      line: 0,
+      column: 0,
+      text: String::new(),
+      line_text: String::new(),
+    }
+  }
+
+  /// Moves the position of an error raised while parsing `inner` so it is relative to `outer`.
+  pub fn within(mut self: Self, outer: &str, inner: &str) -> Self {
+    let Some(inner_offset) = offset_of(outer, inner) else {
+      return self;
+    };
+
+    if self.line == 0 {
+      return self;
+    }
+
+    let (inner_line, inner_column, _) = position_of(outer, inner_offset);
+
+    if self.line == 1 {
+      self.column += inner_column - 1;
+    }
+    self.line += inner_line - 1;
+    self.line_text = outer.split('\n').nth(self.line - 1).unwrap_or("").trim_end_matches('\r').to_string();
+
+    self
+  }
+
+  pub fn in_file(mut self: Self, file: &str) -> Self {
+    self.file = Some(file.to_string());
+    self
+  }
+}
+
+impl fmt::Display for AocError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    writeln!(f, "error: {}", self.message)?;
+
+    let file = self.file.as_deref().unwrap_or("<input>");
+
+    if self.line == 0 {
+      return write!(f, "  --> {}", file);
+    }
+
+    writeln!(f, "  --> {}:{}:{}", file, self.line, self.column)?;
+
+    let gutter = self.line.to_string();
+    let padding = " ".repeat(gutter.len());
+    let underline = "^".repeat(self.text.chars().count().max(1));
+
+    writeln!(f, "{} |", padding)?;
+    writeln!(f, "{} | {}", gutter, self.line_text)?;
+    write!(f, "{} | {}{}", padding, " ".repeat(self.column - 1), underline)
+  }
+}
+
+impl std::error::Error for AocError {}
+
+/// Parses `text`, a slice of `source`, reporting the failure at its position.
+pub fn parse_at<T: FromStr>(source: &str, text: &str) -> Result<T, AocError> {
+  text.parse::<T>().map_err(|_| {
+    let message = if text.is_empty() { "Expected a number".to_string() } else { format!("Expected a number, found '{}'", text) };
+    AocError::new(message, source, text)
+  })
+}
+
+/// Like `parse_at` for unsigned numbers, parsing the digits straight from the bytes.
+pub fn unsigned_at(source: &str, text: &str) -> Result<u64, AocError> {
+  scan::parse_unsigned(text.as_bytes()).ok_or_else(|| {
+    let message = if text.is_empty() { "Expected a number".to_string() } else { format!("Expected a number, found '{}'", text) };
+    AocError::new(message, source, text)
+  })
+}
+
+/// Splits `source` once on `delimiter`, reporting an error at the end of `source` if it is missing.
+pub fn split_once_at<'a>(source: &'a str, delimiter: &str) -> Result<(&'a str, &'a str), AocError> {
+  source.split_once(delimiter)
+    .ok_or_else(|| AocError::end_of_input(format!("Expected '{}'", delimiter), source))
+}
+
+fn offset_of(source: &str, slice: &str) -> Option<usize> {
+  let start = source.as_ptr() as usize;
+  let slice_start = slice.as_ptr() as usize;
+
+  if slice_start < start || slice_start + slice.len() > start + source.len() {
+    return None;
+  }
+
+  Some(slice_start - start)
+}
+
+fn position_of(source: &str, offset: usize) -> (usize, usize, &str) {
+  let before = &source[..offset];
+  let line = before.matches('\n').count() + 1;
+  let line_start = before.rfind('\n').map(|index| index + 1).unwrap_or(0);
+  let column = source[line_start..offset].chars().count() + 1;
+
+  let line_end = source[line_start..].find('\n').map(|index| line_start + index).unwrap_or(source.len());
+  let line_text = source[line_start..line_end].trim_end_matches('\r');
+
+  (line, column, line_text)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn errors_point_at_the_offending_text() {
+    let source = "Game 1: 3 blue\nGame 2: x red";
+    let offending = &source[23..24];
+
+    let error = AocError::new("Expected a number", source, offending);
+
+    assert_eq!((error.line, error.column), (2, 9));
+    assert_eq!(error.line_text, "Game 2: x red");
+    assert_eq!(error.text, "x");
+  }
+
+  #[test]
+  fn errors_can_be_moved_into_an_outer_input() {
+    let input = "first line\nGame 2: 4 blue, x red\n";
+    let line = input.lines().nth(1).unwrap();
+    let set = line.split(':').nth(1).unwrap();
+
+    let error = parse_at::<i32>(set, &set[9..10]).unwrap_err().within(line, set).within(input, line).in_file("day2.txt");
+
+    assert_eq!((error.line, error.column), (2, 17));
+    assert_eq!(error.to_string(), "\
+error: Expected a number, found 'x'
+  --> day2.txt:2:17
+  |
+2 | Game 2: 4 blue, x red
+  |                 ^");
+  }
+
+  #[test]
+  fn missing_delimiters_point_past_the_end() {
+    let error = split_once_at("Card 1 5 6", ":").unwrap_err();
+    assert_eq!((error.line, error.column), (1, 11));
+    assert_eq!(error.message, "Expected ':'");
+  }
+}
@@ -1,10 +1,8 @@
-use std::io::{self, BufRead};
-use std::fs::File;
+use std::io;
+use std::fs;
 use std::path::Path;
 
-pub fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
+pub fn read_file<P>(filename: P) -> io::Result<String>
 where P: AsRef<Path>, {
-  let file = File::open(filename)?;
-
-  Ok(io::BufReader::new(file).lines())
-}
\ No newline at end of file
+  fs::read_to_string(filename)
+}
@@ -0,0 +1,243 @@
+//! Points and compass directions on a grid where `y` grows downwards, as puzzle inputs are read.
+
+use std::fmt;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point {
+  pub x: isize,
+  pub y: isize,
+}
+
+impl Point {
+  pub const ORIGIN: Point = Point { x: 0, y: 0 };
+
+  pub const fn new(x: isize, y: isize) -> Self {
+    Point { x, y }
+  }
+
+  pub fn manhattan(self: &Self, other: Point) -> usize {
+    self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+  }
+
+  /// Distance when diagonal steps count as one, like a king moving on a chessboard.
+  pub fn chebyshev(self: &Self, other: Point) -> usize {
+    self.x.abs_diff(other.x).max(self.y.abs_diff(other.y))
+  }
+
+  /// Rotates a quarter turn clockwise, as seen on screen, around the origin.
+  pub fn rotate_right(self: &Self) -> Point {
+    Point { x: -self.y, y: self.x }
+  }
+
+  /// Rotates a quarter turn anticlockwise, as seen on screen, around the origin.
+  pub fn rotate_left(self: &Self) -> Point {
+    Point { x: self.y, y: -self.x }
+  }
+
+  /// The neighboring point in `direction`.
+  pub fn step(self: &Self, direction: Direction) -> Point {
+    *self + direction.offset()
+  }
+
+  /// The column and row to look up in a `Grid`, `None` if either is negative.
+  pub fn to_index(self: &Self) -> Option<(usize, usize)> {
+    Some((usize::try_from(self.x).ok()?, usize::try_from(self.y).ok()?))
+  }
+}
+
+impl From<(usize, usize)> for Point {
+  fn from((x, y): (usize, usize)) -> Self {
+    Point { x: x as isize, y: y as isize }
+  }
+}
+
+impl fmt::Display for Point {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{},{}", self.x, self.y)
+  }
+}
+
+impl Add for Point {
+  type Output = Point;
+
+  fn add(self, other: Point) -> Point {
+    Point { x: self.x + other.x, y: self.y + other.y }
+  }
+}
+
+impl AddAssign for Point {
+  fn add_assign(&mut self, other: Point) {
+    *self = *self + other;
+  }
+}
+
+impl Sub for Point {
+  type Output = Point;
+
+  fn sub(self, other: Point) -> Point {
+    Point { x: self.x - other.x, y: self.y - other.y }
+  }
+}
+
+impl SubAssign for Point {
+  fn sub_assign(&mut self, other: Point) {
+    *self = *self - other;
+  }
+}
+
+impl Mul<isize> for Point {
+  type Output = Point;
+
+  fn mul(self, scale: isize) -> Point {
+    Point { x: self.x * scale, y: self.y * scale }
+  }
+}
+
+impl Neg for Point {
+  type Output = Point;
+
+  fn neg(self) -> Point {
+    Point { x: -self.x, y: -self.y }
+  }
+}
+
+/// Which way to turn, e.g. day 8's `L`/`R` instructions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Turn {
+  Left,
+  Right,
+}
+
+impl TryFrom<char> for Turn {
+  type Error = String;
+
+  fn try_from(letter: char) -> Result<Self, Self::Error> {
+    match letter {
+      'L' => Ok(Turn::Left),
+      'R' => Ok(Turn::Right),
+      _ => Err(format!("Unexpected turn '{}'", letter)),
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+  North,
+  NorthEast,
+  East,
+  SouthEast,
+  South,
+  SouthWest,
+  West,
+  NorthWest,
+}
+
+impl Direction {
+  /// North, east, south and west, clockwise.
+  pub const CARDINAL: [Direction; 4] = [Direction::North, Direction::East, Direction::South, Direction::West];
+
+  /// Every direction clockwise from north.
+  pub const ALL: [Direction; 8] = [
+    Direction::North, Direction::NorthEast, Direction::East, Direction::SouthEast,
+    Direction::South, Direction::SouthWest, Direction::West, Direction::NorthWest,
+  ];
+
+  /// One step in this direction.
+  pub fn offset(self: &Self) -> Point {
+    match self {
+      Direction::North => Point::new(0, -1),
+      Direction::NorthEast => Point::new(1, -1),
+      Direction::East => Point::new(1, 0),
+      Direction::SouthEast => Point::new(1, 1),
+      Direction::South => Point::new(0, 1),
+      Direction::SouthWest => Point::new(-1, 1),
+      Direction::West => Point::new(-1, 0),
+      Direction::NorthWest => Point::new(-1, -1),
+    }
+  }
+
+  /// A quarter turn anticlockwise.
+  pub fn turn_left(self: &Self) -> Direction {
+    self.rotate(6)
+  }
+
+  /// A quarter turn clockwise.
+  pub fn turn_right(self: &Self) -> Direction {
+    self.rotate(2)
+  }
+
+  pub fn turn(self: &Self, turn: Turn) -> Direction {
+    match turn {
+      Turn::Left => self.turn_left(),
+      Turn::Right => self.turn_right(),
+    }
+  }
+
+  pub fn reverse(self: &Self) -> Direction {
+    self.rotate(4)
+  }
+
+  /// Turns clockwise by `eighths` of a full turn.
+  fn rotate(self: &Self, eighths: usize) -> Direction {
+    let index = Direction::ALL.iter().position(|direction| direction == self).expect("Every direction is in ALL");
+    Direction::ALL[(index + eighths) % 8]
+  }
+}
+
+/// Compass letters `N`/`E`/`S`/`W`, or screen letters `U`/`D`/`L`/`R`.
+impl TryFrom<char> for Direction {
+  type Error = String;
+
+  fn try_from(letter: char) -> Result<Self, Self::Error> {
+    match letter {
+      'N' | 'U' => Ok(Direction::North),
+      'E' | 'R' => Ok(Direction::East),
+      'S' | 'D' => Ok(Direction::South),
+      'W' | 'L' => Ok(Direction::West),
+      _ => Err(format!("Unexpected direction '{}'", letter)),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn points_add_scale_and_measure() {
+    let a = Point::new(1, -2);
+    let b = Point::new(4, 2);
+
+    assert_eq!(a + b, Point::new(5, 0));
+    assert_eq!(b - a, Point::new(3, 4));
+    assert_eq!(a * 3, Point::new(3, -6));
+    assert_eq!(-a, Point::new(-1, 2));
+    assert_eq!(a.manhattan(b), 7);
+    assert_eq!(a.chebyshev(b), 4);
+    assert_eq!(Point::new(2, 1).rotate_right(), Point::new(-1, 2));
+    assert_eq!(Point::new(2, 1).rotate_left(), Point::new(1, -2));
+    assert_eq!(a.rotate_right().rotate_left(), a);
+    assert_eq!(Point::new(0, 3).to_index(), Some((0, 3)));
+    assert_eq!(a.to_index(), None);
+  }
+
+  #[test]
+  fn directions_turn_and_reverse() {
+    assert_eq!(Direction::North.turn_right(), Direction::East);
+    assert_eq!(Direction::North.turn_left(), Direction::West);
+    assert_eq!(Direction::NorthEast.turn(Turn::Left), Direction::NorthWest);
+    assert_eq!(Direction::SouthWest.reverse(), Direction::NorthEast);
+
+    for direction in Direction::ALL {
+      assert_eq!(direction.offset().rotate_right(), direction.turn_right().offset());
+      assert_eq!(direction.reverse().offset(), -direction.offset());
+    }
+
+    assert_eq!(Point::ORIGIN.step(Direction::North), Point::new(0, -1));
+    assert_eq!(Direction::try_from('U'), Ok(Direction::North));
+    assert_eq!(Direction::try_from('L'), Ok(Direction::West));
+    assert_eq!(Turn::try_from('R'), Ok(Turn::Right));
+    assert!(Turn::try_from('U').is_err());
+  }
+}
@@ -0,0 +1,190 @@
+use std::fmt;
+use std::ops::Range;
+use std::str::FromStr;
+
+use crate::utils::error::AocError;
+
+const NEIGHBORS_4: [(isize, isize); 4] = [(0, -1), (-1, 0), (1, 0), (0, 1)];
+const NEIGHBORS_8: [(isize, isize); 8] = [(-1, -1), (0, -1), (1, -1), (-1, 0), (1, 0), (-1, 1), (0, 1), (1, 1)];
+
+/// A rectangle of cells stored row by row. `x` is the column and `y` the row, both counted from
+/// the top left.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+  width: usize,
+  height: usize,
+  cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+  /// Builds a grid from its rows, which must all be the same length.
+  pub fn from_rows(rows: Vec<Vec<T>>) -> Result<Self, String> {
+    let width = rows.first().map_or(0, |row| row.len());
+    let height = rows.len();
+
+    if let Some(y) = rows.iter().position(|row| row.len() != width) {
+      return Err(format!("Row {} is {} cells wide, expected {}", y, rows[y].len(), width));
+    }
+
+    Ok(Grid { width, height, cells: rows.into_iter().flatten().collect() })
+  }
+
+  pub fn filled(width: usize, height: usize, value: T) -> Self
+  where T: Clone, {
+    Grid { width, height, cells: vec![value; width * height] }
+  }
+
+  pub fn width(self: &Self) -> usize {
+    self.width
+  }
+
+  pub fn height(self: &Self) -> usize {
+    self.height
+  }
+
+  /// The cell at `x`, `y`, or `None` outside the grid.
+  pub fn get(self: &Self, x: usize, y: usize) -> Option<&T> {
+    self.index(x, y).map(|index| &self.cells[index])
+  }
+
+  pub fn get_mut(self: &mut Self, x: usize, y: usize) -> Option<&mut T> {
+    self.index(x, y).map(|index| &mut self.cells[index])
+  }
+
+  /// Every cell with its position, row by row.
+  pub fn iter(self: &Self) -> impl Iterator<Item = ((usize, usize), &T)> {
+    self.cells.iter().enumerate().map(|(index, cell)| ((index % self.width, index / self.width), cell))
+  }
+
+  /// The cells above, left, right and below `x`, `y` that are inside the grid.
+  pub fn neighbors_4(self: &Self, x: usize, y: usize) -> impl Iterator<Item = ((usize, usize), &T)> {
+    self.offsets(x, y, &NEIGHBORS_4)
+  }
+
+  /// Like `neighbors_4`, with the diagonals as well.
+  pub fn neighbors_8(self: &Self, x: usize, y: usize) -> impl Iterator<Item = ((usize, usize), &T)> {
+    self.offsets(x, y, &NEIGHBORS_8)
+  }
+
+  /// Row `y` from left to right. Panics if `y` is outside the grid.
+  pub fn row(self: &Self, y: usize) -> &[T] {
+    assert!(y < self.height, "Row {} is outside a grid {} rows high", y, self.height);
+
+    &self.cells[y * self.width..(y + 1) * self.width]
+  }
+
+  pub fn rows(self: &Self) -> impl Iterator<Item = &[T]> {
+    (0..self.height).map(|y| self.row(y))
+  }
+
+  /// Column `x` from top to bottom. Panics if `x` is outside the grid.
+  pub fn column(self: &Self, x: usize) -> impl Iterator<Item = &T> {
+    assert!(x < self.width, "Column {} is outside a grid {} columns wide", x, self.width);
+
+    self.cells.iter().skip(x).step_by(self.width)
+  }
+
+  /// The cells in `columns` and `rows` with their positions, row by row. Whatever part of the
+  /// rectangle hangs off the grid is skipped.
+  pub fn region(self: &Self, columns: Range<usize>, rows: Range<usize>) -> impl Iterator<Item = ((usize, usize), &T)> {
+    let columns = columns.start.min(self.width)..columns.end.min(self.width);
+    let rows = rows.start.min(self.height)..rows.end.min(self.height);
+
+    rows.flat_map(move |y| columns.clone().map(move |x| ((x, y), &self.cells[y * self.width + x])))
+  }
+
+  fn index(self: &Self, x: usize, y: usize) -> Option<usize> {
+    match x < self.width && y < self.height {
+      true => Some(y * self.width + x),
+      false => None,
+    }
+  }
+
+  fn offsets<'a>(self: &'a Self, x: usize, y: usize, offsets: &'static [(isize, isize)]) -> impl Iterator<Item = ((usize, usize), &'a T)> {
+    offsets.iter().filter_map(move |(dx, dy)| {
+      let (x, y) = (x.checked_add_signed(*dx)?, y.checked_add_signed(*dy)?);
+      self.get(x, y).map(|cell| ((x, y), cell))
+    })
+  }
+}
+
+/// One cell per character, one row per line. Rows must all be the same width.
+impl<T> FromStr for Grid<T>
+where T: TryFrom<char>, T::Error: fmt::Display, {
+  type Err = AocError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let mut rows = vec![];
+
+    for line in s.lines() {
+      let row = line.char_indices()
+        .map(|(index, c)| T::try_from(c).map_err(|error| AocError::new(error.to_string(), s, &line[index..index + c.len_utf8()])))
+        .collect::<Result<Vec<T>, AocError>>()?;
+
+      if let Some(first) = rows.first().map(|first: &Vec<T>| first.len()).filter(|width| *width != row.len()) {
+        return Err(AocError::new(format!("Expected a row {} cells wide, got {}", first, row.len()), s, line));
+      }
+
+      rows.push(row);
+    }
+
+    Ok(Grid::from_rows(rows).expect("Row widths were checked while parsing"))
+  }
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    for (y, row) in self.rows().enumerate() {
+      if y > 0 {
+        writeln!(f)?;
+      }
+      for cell in row {
+        write!(f, "{}", cell)?;
+      }
+    }
+
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn grids_parse_row_major_and_print_back() {
+    let grid: Grid<char> = "abc\r\ndef\n".parse().unwrap();
+
+    assert_eq!((grid.width(), grid.height()), (3, 2));
+    assert_eq!(grid.get(2, 0), Some(&'c'));
+    assert_eq!(grid.get(0, 1), Some(&'d'));
+    assert_eq!(grid.get(3, 0), None);
+    assert_eq!(grid.get(0, 2), None);
+    assert_eq!(grid.row(1), &['d', 'e', 'f']);
+    assert_eq!(grid.column(1).collect::<String>(), "be");
+    assert_eq!(grid.to_string(), "abc\ndef");
+
+    let error = "abc\nde".parse::<Grid<char>>().unwrap_err();
+    assert_eq!(error.message, "Expected a row 3 cells wide, got 2");
+    assert_eq!((error.line, error.column), (2, 1));
+  }
+
+  #[test]
+  fn neighbors_and_regions_stay_inside_the_grid() {
+    let grid: Grid<char> = "abc\ndef\nghi".parse().unwrap();
+
+    let cells = |iter: &mut dyn Iterator<Item = ((usize, usize), &char)>| iter.map(|(_, cell)| *cell).collect::<String>();
+
+    assert_eq!(cells(&mut grid.neighbors_4(1, 1)), "bdfh");
+    assert_eq!(cells(&mut grid.neighbors_8(1, 1)), "abcdfghi");
+    assert_eq!(cells(&mut grid.neighbors_8(0, 0)), "bde");
+    assert_eq!(cells(&mut grid.neighbors_4(2, 2)), "fh");
+    assert_eq!(cells(&mut grid.region(1..5, 1..3)), "efhi");
+    assert_eq!(grid.region(0..2, 0..1).map(|(position, _)| position).collect::<Vec<(usize, usize)>>(), vec![(0, 0), (1, 0)]);
+
+    let mut grid = Grid::filled(2, 2, 0);
+    *grid.get_mut(1, 1).unwrap() = 5;
+    assert_eq!(grid.iter().filter(|(_, cell)| **cell == 5).map(|(position, _)| position).collect::<Vec<(usize, usize)>>(), vec![(1, 1)]);
+    assert!(Grid::from_rows(vec![vec![1, 2], vec![3]]).is_err());
+  }
+}
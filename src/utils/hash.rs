@@ -0,0 +1,32 @@
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+/// 64-bit FNV-1a. Unlike `DefaultHasher` the result is stable across Rust versions,
+/// so it can be stored and compared between runs.
+pub fn fnv1a_64(bytes: &[u8]) -> u64 {
+  let mut hash = FNV_OFFSET_BASIS;
+
+  for byte in bytes {
+    hash ^= *byte as u64;
+    hash = hash.wrapping_mul(FNV_PRIME);
+  }
+
+  hash
+}
+
+/// The hash of an input file's contents as it appears in run reports.
+pub fn input_hash(contents: &str) -> String {
+  format!("{:016x}", fnv1a_64(contents.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn matches_the_reference_vectors() {
+    assert_eq!(fnv1a_64(b""), 0xcbf29ce484222325);
+    assert_eq!(fnv1a_64(b"a"), 0xaf63dc4c8601ec8c);
+    assert_eq!(input_hash("foobar"), "85944171f73967e8");
+  }
+}
@@ -0,0 +1,189 @@
+use std::io::{Read, Write};
+use std::net::{IpAddr, TcpStream};
+use std::time::Duration;
+
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A plain `http://host[:port]/path` URL. There is no TLS support.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Url {
+  pub host: String,
+  pub port: u16,
+  pub path: String,
+}
+
+impl Url {
+  pub fn parse(url: &str) -> Result<Self, String> {
+    let rest = match url.strip_prefix("http://") {
+      Some(rest) => rest,
+      None if url.starts_with("https://") => return Err(format!("Only plain http:// URLs are supported, point the base URL at a local proxy instead of {}", url)),
+      None => return Err(format!("Expected an http:// URL, got '{}'", url)),
+    };
+
+    let (authority, path) = match rest.find('/') {
+      Some(index) => (&rest[..index], &rest[index..]),
+      None => (rest, "/"),
+    };
+
+    let (host, port) = match authority.rsplit_once(':') {
+      Some((host, port)) => (host, port.parse::<u16>().map_err(|_| format!("Invalid port in '{}'", url))?),
+      None => (authority, 80),
+    };
+
+    if host.is_empty() {
+      return Err(format!("Missing host in '{}'", url));
+    }
+
+    Ok(Url { host: host.to_string(), port, path: path.to_string() })
+  }
+
+  /// Whether the host is this machine, e.g. `localhost` or `127.0.0.1`.
+  pub fn is_loopback(self: &Self) -> bool {
+    let host = self.host.trim_start_matches('[').trim_end_matches(']');
+    host.eq_ignore_ascii_case("localhost") || host.parse::<IpAddr>().is_ok_and(|address| address.is_loopback())
+  }
+
+  /// Appends `path` to this URL's path, avoiding a doubled `/`.
+  pub fn join(self: &Self, path: &str) -> Url {
+    Url {
+      host: self.host.clone(),
+      port: self.port,
+      path: format!("{}/{}", self.path.trim_end_matches('/'), path.trim_start_matches('/')),
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+  pub status: u16,
+  pub headers: Vec<(String, String)>,
+  pub body: String,
+}
+
+impl Response {
+  /// Looks up a header by name, ignoring case.
+  pub fn header(self: &Self, name: &str) -> Option<&str> {
+    self.headers.iter().find(|(header, _)| header.eq_ignore_ascii_case(name)).map(|(_, value)| value.as_str())
+  }
+}
+
+/// Sends a single HTTP/1.1 request and reads the whole response. `headers` are sent as given,
+/// after `Host`, `Connection` and (with a body) `Content-Length`. Since nothing is encrypted, a
+/// `Cookie` header is only sent to a loopback host.
+pub fn request(method: &str, url: &Url, headers: &[(&str, &str)], body: Option<&str>) -> Result<Response, String> {
+  if !url.is_loopback() && headers.iter().any(|(name, _)| name.eq_ignore_ascii_case("Cookie")) {
+    return Err(format!("Refusing to send the session cookie in cleartext to {}, point the base URL at a local proxy", url.host));
+  }
+
+  let address = format!("{}:{}", url.host, url.port);
+  let mut stream = TcpStream::connect(&address).map_err(|error| format!("Could not connect to {}: {}", address, error))?;
+  stream.set_read_timeout(Some(TIMEOUT)).and_then(|_| stream.set_write_timeout(Some(TIMEOUT)))
+    .map_err(|error| error.to_string())?;
+
+  let mut request = format!("{} {} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n", method, url.path, url.host);
+  for (name, value) in headers {
+    request.push_str(&format!("{}: {}\r\n", name, value));
+  }
+  if let Some(body) = body {
+    request.push_str(&format!("Content-Length: {}\r\n", body.len()));
+  }
+  request.push_str("\r\n");
+  request.push_str(body.unwrap_or(""));
+
+  stream.write_all(request.as_bytes()).map_err(|error| format!("Could not send request to {}: {}", address, error))?;
+
+  let mut raw = vec![];
+  stream.read_to_end(&mut raw).map_err(|error| format!("Could not read response from {}: {}", address, error))?;
+
+  parse_response(&raw)
+}
+
+fn parse_response(raw: &[u8]) -> Result<Response, String> {
+  let header_end = raw.windows(4).position(|window| window == b"\r\n\r\n")
+    .ok_or("Response ended before its headers did")?;
+
+  let head = String::from_utf8_lossy(&raw[..header_end]);
+  let body = &raw[header_end + 4..];
+
+  let mut lines = head.split("\r\n");
+  let status_line = lines.next().unwrap_or("");
+  let status = status_line.split(' ').nth(1).and_then(|status| status.parse::<u16>().ok())
+    .ok_or_else(|| format!("Invalid status line '{}'", status_line))?;
+
+  let headers: Vec<(String, String)> = lines
+    .filter_map(|line| line.split_once(':'))
+    .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+    .collect();
+
+  let mut response = Response { status, headers, body: String::new() };
+
+  let body = if response.header("Transfer-Encoding").is_some_and(|encoding| encoding.eq_ignore_ascii_case("chunked")) {
+    decode_chunked(body)?
+  } else {
+    match response.header("Content-Length").and_then(|length| length.parse::<usize>().ok()) {
+      Some(length) => body[..length.min(body.len())].to_vec(),
+      None => body.to_vec(),
+    }
+  };
+
+  response.body = String::from_utf8(body).map_err(|_| "Response body is not valid UTF-8".to_string())?;
+
+  Ok(response)
+}
+
+fn decode_chunked(mut body: &[u8]) -> Result<Vec<u8>, String> {
+  let mut decoded = vec![];
+
+  loop {
+    let line_end = body.windows(2).position(|window| window == b"\r\n").ok_or("Truncated chunked body")?;
+    let size = String::from_utf8_lossy(&body[..line_end]);
+    let size = usize::from_str_radix(size.split(';').next().unwrap_or("").trim(), 16)
+      .map_err(|_| format!("Invalid chunk size '{}'", size))?;
+
+    body = &body[line_end + 2..];
+
+    if size == 0 {
+      return Ok(decoded);
+    }
+
+    if body.len() < size {
+      return Err("Truncated chunked body".to_string());
+    }
+
+    decoded.extend_from_slice(&body[..size]);
+    body = body.get(size + 2..).unwrap_or(&[]);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn urls_split_into_host_port_and_path() {
+    assert_eq!(Url::parse("http://localhost:8080/aoc/"), Ok(Url { host: "localhost".to_string(), port: 8080, path: "/aoc/".to_string() }));
+    assert_eq!(Url::parse("http://example.com").unwrap().join("2023/day/1/input").path, "/2023/day/1/input");
+    assert!(Url::parse("https://example.com").is_err());
+    assert!(Url::parse("http://:80/").is_err());
+  }
+
+  #[test]
+  fn session_cookies_only_go_to_loopback_hosts() {
+    assert!(Url::parse("http://localhost:8080").unwrap().is_loopback());
+    assert!(Url::parse("http://127.0.0.1/").unwrap().is_loopback());
+    assert!(Url::parse("http://[::1]:8080/").unwrap().is_loopback());
+    assert!(!Url::parse("http://adventofcode.com").unwrap().is_loopback());
+
+    let error = request("GET", &Url::parse("http://adventofcode.com/2023/day/1/input").unwrap(), &[("Cookie", "session=s3cret")], None).unwrap_err();
+    assert!(error.starts_with("Refusing to send the session cookie"));
+  }
+
+  #[test]
+  fn chunked_and_sized_bodies_are_decoded() {
+    let response = parse_response(b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n4\r\n1 2 \r\n3\r\n3\n\n\r\n0\r\n\r\n").unwrap();
+    assert_eq!(response.body, "1 2 3\n\n");
+
+    let response = parse_response(b"HTTP/1.1 404 Not Found\r\ncontent-length: 3\r\n\r\n404 and more").unwrap();
+    assert_eq!((response.status, response.body.as_str()), (404, "404"));
+  }
+}
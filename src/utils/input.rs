@@ -0,0 +1,121 @@
+use crate::utils::error::{self, AocError};
+
+/// A puzzle input that reads the same whether it was checked out with CRLF or LF line endings.
+/// Nothing is copied: `lines`, `sections` and `Section::split_header` hand out slices of the
+/// original text with any `\r` before a line break left out, so errors built against `text()`
+/// get the right line and column.
+#[derive(Debug, Clone, Copy)]
+pub struct Input<'a> {
+  text: &'a str,
+}
+
+impl<'a> Input<'a> {
+  pub fn new(text: &'a str) -> Self {
+    Input { text }
+  }
+
+  pub fn text(self: &Self) -> &'a str {
+    self.text
+  }
+
+  pub fn lines(self: &Self) -> impl Iterator<Item = &'a str> {
+    self.text.lines()
+  }
+
+  /// The blocks of lines separated by blank lines. Runs of blank lines, and blank lines at the
+  /// start or end of the input, never produce empty sections.
+  pub fn sections(self: &Self) -> impl Iterator<Item = Section<'a>> {
+    let text = self.text;
+    let mut sections = vec![];
+    let mut start: Option<usize> = None;
+    let mut end = 0;
+    let mut offset = 0;
+
+    for line in text.split_inclusive('\n') {
+      let content = line.trim_end_matches('\n').trim_end_matches('\r');
+
+      if content.trim().is_empty() {
+        if let Some(section_start) = start.take() {
+          sections.push(Section { source: text, text: &text[section_start..end] });
+        }
+      } else {
+        start.get_or_insert(offset);
+        end = offset + content.len();
+      }
+
+      offset += line.len();
+    }
+
+    if let Some(section_start) = start {
+      sections.push(Section { source: text, text: &text[section_start..end] });
+    }
+
+    sections.into_iter()
+  }
+}
+
+/// A blank-line separated block of an `Input`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Section<'a> {
+  source: &'a str,
+  pub text: &'a str,
+}
+
+impl<'a> Section<'a> {
+  pub fn lines(self: &Self) -> impl Iterator<Item = &'a str> {
+    self.text.lines()
+  }
+
+  /// Splits `seeds: 79 14` or `seed-to-soil map:\n50 98 2` into the part before the first `:` and
+  /// the part after it, both trimmed. Errors point into the whole input.
+  pub fn split_header(self: &Self) -> Result<(&'a str, &'a str), AocError> {
+    split_header(self.text).map_err(|error| error.within(self.source, self.text))
+  }
+}
+
+/// Splits a `Game 12: ...` style line into its trimmed header and body around the first `:`.
+pub fn split_header(text: &str) -> Result<(&str, &str), AocError> {
+  let (header, body) = error::split_once_at(text, ":")?;
+
+  Ok((header.trim(), body.trim()))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn crlf_and_lf_inputs_have_the_same_sections() {
+    let lf = "seeds: 79 14\n\nseed-to-soil map:\n50 98 2\n52 50 48\n";
+    let crlf = lf.replace('\n', "\r\n");
+
+    for raw in [lf, crlf.as_str()] {
+      let input = Input::new(raw);
+      let sections: Vec<Section> = input.sections().collect();
+
+      assert_eq!(sections.len(), 2);
+      assert_eq!(sections[0].split_header().unwrap(), ("seeds", "79 14"));
+      let (header, body) = sections[1].split_header().unwrap();
+      assert_eq!(header, "seed-to-soil map");
+      assert_eq!(body.lines().collect::<Vec<&str>>(), vec!["50 98 2", "52 50 48"]);
+      assert_eq!(input.lines().nth(3), Some("50 98 2"));
+    }
+  }
+
+  #[test]
+  fn blank_runs_do_not_make_empty_sections() {
+    let input = Input::new("\n\na\nb\n \n\n\nc\n\n");
+    let sections: Vec<&str> = input.sections().map(|section| section.text).collect();
+
+    assert_eq!(sections, vec!["a\nb", "c"]);
+  }
+
+  #[test]
+  fn missing_headers_point_into_the_whole_input() {
+    let input = Input::new("seeds: 1 2\r\n\r\n50 98 2\r\n");
+    let error = input.sections().nth(1).unwrap().split_header().unwrap_err();
+
+    assert_eq!(error.message, "Expected ':'");
+    assert_eq!((error.line, error.column), (3, 8));
+  }
+}
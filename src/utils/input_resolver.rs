@@ -0,0 +1,180 @@
+use std::env;
+use std::io::{self, Read};
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+use crate::utils::error::AocError;
+use crate::utils::file_utils;
+
+pub const INPUT_DIRECTORY_ENV: &str = "AOC_INPUT_DIR";
+
+pub const DEFAULT_INPUT_DIRECTORY: &str = "./inputs";
+
+/// Passing this as an input path reads the puzzle from stdin instead.
+pub const STDIN_INPUT: &str = "-";
+
+static INPUT_DIRECTORY: OnceLock<String> = OnceLock::new();
+static STDIN_CONTENTS: OnceLock<Result<String, String>> = OnceLock::new();
+
+/// Which of a day's input files to use.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub enum InputKind {
+  /// The personal puzzle input, `dayNpart1.txt`. Both parts share it.
+  #[default]
+  Real,
+  /// The example from the puzzle text, `dayNtest.txt`.
+  Example,
+  /// Any other variant, `dayN<name>.txt`.
+  Named(String),
+}
+
+impl FromStr for InputKind {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s {
+      "" => Err("Input variant name can't be empty".to_string()),
+      "real" | "part1" => Ok(InputKind::Real),
+      "example" | "test" => Ok(InputKind::Example),
+      name if name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') => Ok(InputKind::Named(name.to_string())),
+      name => Err(format!("Input variant '{}' may only contain letters, digits, '-' and '_'", name)),
+    }
+  }
+}
+
+impl InputKind {
+  /// The name `--variant` takes for this kind, e.g. `real` or `example`.
+  pub fn name(self: &Self) -> &str {
+    match self {
+      InputKind::Real => "real",
+      InputKind::Example => "example",
+      InputKind::Named(name) => name,
+    }
+  }
+}
+
+/// Maps a day and input kind onto a file in the input directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputResolver {
+  directory: String,
+}
+
+impl Default for InputResolver {
+  fn default() -> Self {
+    InputResolver { directory: DEFAULT_INPUT_DIRECTORY.to_string() }
+  }
+}
+
+impl InputResolver {
+  pub fn new(directory: &str) -> Self {
+    InputResolver { directory: directory.trim_end_matches('/').to_string() }
+  }
+
+  /// The resolver used by the runner: the directory set with `set_directory`, then
+  /// the `AOC_INPUT_DIR` environment variable, then `./inputs`.
+  pub fn current() -> Self {
+    let directory = INPUT_DIRECTORY.get()
+      .cloned()
+      .or_else(|| env::var(INPUT_DIRECTORY_ENV).ok().filter(|directory| !directory.is_empty()));
+
+    match directory {
+      Some(directory) => InputResolver::new(&directory),
+      None => InputResolver::default(),
+    }
+  }
+
+  /// Overrides the input directory for the rest of the run. Only the first call has any effect.
+  pub fn set_directory(directory: &str) {
+    let _ = INPUT_DIRECTORY.set(directory.to_string());
+  }
+
+  pub fn directory(self: &Self) -> &str {
+    &self.directory
+  }
+
+  pub fn path(self: &Self, day: u8, kind: &InputKind) -> String {
+    let suffix = match kind {
+      InputKind::Real => "part1",
+      InputKind::Example => "test",
+      InputKind::Named(name) => name,
+    };
+
+    format!("{}/day{}{}.txt", self.directory, day, suffix)
+  }
+
+  /// The path relative to the input directory if it lives there, otherwise the path as given.
+  pub fn relative<'a>(self: &Self, path: &'a str) -> &'a str {
+    let directory = self.directory.strip_prefix("./").unwrap_or(&self.directory);
+
+    path.strip_prefix("./").unwrap_or(path)
+      .strip_prefix(directory)
+      .and_then(|rest| rest.strip_prefix('/'))
+      .unwrap_or(path)
+  }
+
+  /// Reads an explicit input path (or `-` for stdin), falling back to the day's real input.
+  /// Returns the path that was read alongside its contents.
+  pub fn read(self: &Self, day: u8, inputFile: Option<&str>) -> Result<(String, String), AocError> {
+    let inputFile = inputFile.map(String::from).unwrap_or_else(|| self.path(day, &InputKind::Real));
+
+    if inputFile == STDIN_INPUT {
+      return read_stdin().map(|contents| ("<stdin>".to_string(), contents));
+    }
+
+    match file_utils::read_file(Path::new(&inputFile)) {
+      Ok(contents) => Ok((inputFile, contents)),
+      Err(error) => Err(AocError::io(&inputFile, error)),
+    }
+  }
+}
+
+/// Stdin can only be consumed once, so it is read on first use and shared by every part after that.
+fn read_stdin() -> Result<String, AocError> {
+  let contents = STDIN_CONTENTS.get_or_init(|| {
+    let mut contents = String::new();
+    io::stdin().read_to_string(&mut contents).map(|_| contents).map_err(|error| error.to_string())
+  });
+
+  contents.clone().map_err(|error| AocError::io("<stdin>", io::Error::other(error)))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn kinds_map_onto_the_existing_file_names() {
+    let resolver = InputResolver::new("./inputs/");
+
+    assert_eq!(resolver.path(5, &InputKind::Real), "./inputs/day5part1.txt");
+    assert_eq!(resolver.path(6, &InputKind::Example), "./inputs/day6test.txt");
+    assert_eq!(resolver.path(7, &"large".parse().unwrap()), "./inputs/day7large.txt");
+  }
+
+  #[test]
+  fn kinds_parse_from_their_names() {
+    assert_eq!("real".parse(), Ok(InputKind::Real));
+    assert_eq!("example".parse(), Ok(InputKind::Example));
+    assert_eq!("edge-case".parse(), Ok(InputKind::Named("edge-case".to_string())));
+    assert_eq!(InputKind::Example.name(), "example");
+    assert!("../secrets".parse::<InputKind>().is_err());
+  }
+
+  #[test]
+  fn relative_paths_strip_the_input_directory() {
+    let resolver = InputResolver::new("./inputs");
+
+    assert_eq!(resolver.relative("./inputs/day1part1.txt"), "day1part1.txt");
+    assert_eq!(resolver.relative("inputs/day1part1.txt"), "day1part1.txt");
+    assert_eq!(resolver.relative("/tmp/day1.txt"), "/tmp/day1.txt");
+    assert_eq!(resolver.relative("./other/day1.txt"), "./other/day1.txt");
+  }
+
+  #[test]
+  fn missing_files_are_reported_with_their_path() {
+    let error = InputResolver::new("./does-not-exist").read(3, None).unwrap_err();
+
+    assert_eq!(error.file.as_deref(), Some("./does-not-exist/day3part1.txt"));
+  }
+}
@@ -0,0 +1,242 @@
+//! Sets of values stored as sorted half-open ranges, for puzzles that push whole ranges of numbers
+//! around instead of single values.
+
+use std::ops::Range;
+
+/// A set of `u64`s, `i64`s or similar, kept as sorted ranges that neither overlap nor touch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IntervalSet<T> {
+  ranges: Vec<Range<T>>,
+}
+
+impl<T> Default for IntervalSet<T> {
+  fn default() -> Self {
+    IntervalSet { ranges: vec![] }
+  }
+}
+
+impl<T: Copy + Ord> IntervalSet<T> {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Adds every value in `range`, merging it with any range it overlaps or touches.
+  pub fn insert(self: &mut Self, range: Range<T>) {
+    if range.is_empty() {
+      return;
+    }
+
+    let first = self.ranges.partition_point(|existing| existing.end < range.start);
+    let last = self.ranges.partition_point(|existing| existing.start <= range.end);
+
+    let merged = match first < last {
+      true => range.start.min(self.ranges[first].start)..range.end.max(self.ranges[last - 1].end),
+      false => range,
+    };
+
+    self.ranges.splice(first..last, [merged]);
+  }
+
+  /// Takes every value in `range` out of the set.
+  pub fn remove(self: &mut Self, range: Range<T>) {
+    if range.is_empty() {
+      return;
+    }
+
+    let first = self.ranges.partition_point(|existing| existing.end <= range.start);
+    let last = self.ranges.partition_point(|existing| existing.start < range.end);
+
+    if first >= last {
+      return;
+    }
+
+    let left = self.ranges[first].start..range.start;
+    let right = range.end..self.ranges[last - 1].end;
+
+    self.ranges.splice(first..last, [left, right].into_iter().filter(|piece| !piece.is_empty()));
+  }
+
+  pub fn contains(self: &Self, value: &T) -> bool {
+    let index = self.ranges.partition_point(|range| range.end <= *value);
+    self.ranges.get(index).is_some_and(|range| range.start <= *value)
+  }
+
+  pub fn is_empty(self: &Self) -> bool {
+    self.ranges.is_empty()
+  }
+
+  /// How many separate ranges the set is made of. See `cardinality` for the number of values.
+  pub fn len(self: &Self) -> usize {
+    self.ranges.len()
+  }
+
+  pub fn min(self: &Self) -> Option<T> {
+    self.ranges.first().map(|range| range.start)
+  }
+
+  /// The ranges from lowest to highest.
+  pub fn iter(self: &Self) -> std::slice::Iter<'_, Range<T>> {
+    self.ranges.iter()
+  }
+
+  /// Every value in the set from lowest to highest.
+  pub fn values(self: &Self) -> impl Iterator<Item = T> + '_
+  where Range<T>: Iterator<Item = T>, {
+    self.ranges.iter().flat_map(|range| range.clone())
+  }
+
+  pub fn union(self: &Self, other: &Self) -> Self {
+    let mut union = self.clone();
+    for range in other {
+      union.insert(range.clone());
+    }
+    union
+  }
+
+  pub fn difference(self: &Self, other: &Self) -> Self {
+    let mut difference = self.clone();
+    for range in other {
+      difference.remove(range.clone());
+    }
+    difference
+  }
+
+  pub fn intersection(self: &Self, other: &Self) -> Self {
+    let mut ranges = vec![];
+    let (mut a, mut b) = (0, 0);
+
+    while a < self.ranges.len() && b < other.ranges.len() {
+      let (left, right) = (&self.ranges[a], &other.ranges[b]);
+
+      let overlap = left.start.max(right.start)..left.end.min(right.end);
+      if !overlap.is_empty() {
+        ranges.push(overlap);
+      }
+
+      match left.end < right.end {
+        true => a += 1,
+        false => b += 1,
+      }
+    }
+
+    IntervalSet { ranges }
+  }
+
+  /// Cuts the set's ranges at every boundary that falls inside them, so no piece straddles a
+  /// boundary. The pieces come back in order and, unlike the set, may touch.
+  pub fn split_by(self: &Self, boundaries: impl IntoIterator<Item = T>) -> Vec<Range<T>> {
+    let mut boundaries: Vec<T> = boundaries.into_iter().collect();
+    boundaries.sort_unstable();
+    boundaries.dedup();
+
+    let mut pieces = vec![];
+
+    for range in &self.ranges {
+      let mut start = range.start;
+      let inside = boundaries.partition_point(|boundary| *boundary <= range.start);
+
+      for boundary in boundaries[inside..].iter().take_while(|boundary| **boundary < range.end) {
+        pieces.push(start..*boundary);
+        start = *boundary;
+      }
+
+      pieces.push(start..range.end);
+    }
+
+    pieces
+  }
+}
+
+impl<T: Copy + Ord + Into<i128>> IntervalSet<T> {
+  /// How many values the set holds.
+  pub fn cardinality(self: &Self) -> u128 {
+    self.ranges.iter().map(|range| (range.end.into() - range.start.into()) as u128).sum()
+  }
+}
+
+impl<T: Copy + Ord> FromIterator<Range<T>> for IntervalSet<T> {
+  fn from_iter<I: IntoIterator<Item = Range<T>>>(ranges: I) -> Self {
+    let mut set = IntervalSet::new();
+    for range in ranges {
+      set.insert(range);
+    }
+    set
+  }
+}
+
+impl<T: Copy + Ord> Extend<Range<T>> for IntervalSet<T> {
+  fn extend<I: IntoIterator<Item = Range<T>>>(&mut self, ranges: I) {
+    for range in ranges {
+      self.insert(range);
+    }
+  }
+}
+
+impl<'a, T> IntoIterator for &'a IntervalSet<T> {
+  type Item = &'a Range<T>;
+  type IntoIter = std::slice::Iter<'a, Range<T>>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.ranges.iter()
+  }
+}
+
+impl<T> IntoIterator for IntervalSet<T> {
+  type Item = Range<T>;
+  type IntoIter = std::vec::IntoIter<Range<T>>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.ranges.into_iter()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn ranges<T: Copy + Ord>(set: &IntervalSet<T>) -> Vec<Range<T>> {
+    set.iter().cloned().collect()
+  }
+
+  #[test]
+  fn inserts_coalesce_and_removes_split() {
+    let mut set: IntervalSet<u64> = [10..20, 30..40, 0..5].into_iter().collect();
+    assert_eq!(ranges(&set), vec![0..5, 10..20, 30..40]);
+
+    set.insert(20..25);
+    set.insert(5..7);
+    set.insert(8..8);
+    assert_eq!(ranges(&set), vec![0..7, 10..25, 30..40]);
+
+    set.insert(6..31);
+    assert_eq!(ranges(&set), vec![0..40]);
+
+    set.remove(10..20);
+    set.remove(35..50);
+    assert_eq!(ranges(&set), vec![0..10, 20..35]);
+    assert!(set.contains(&9) && !set.contains(&10) && set.contains(&20) && !set.contains(&35));
+    assert_eq!((set.len(), set.cardinality(), set.min()), (2, 25, Some(0)));
+  }
+
+  #[test]
+  fn set_operations_work_on_signed_ranges() {
+    let a: IntervalSet<i64> = [-10..0, 5..15].into_iter().collect();
+    let b: IntervalSet<i64> = [-5..8, 12..20].into_iter().collect();
+
+    assert_eq!(ranges(&a.union(&b)), vec![-10..20]);
+    assert_eq!(ranges(&a.intersection(&b)), vec![-5..0, 5..8, 12..15]);
+    assert_eq!(ranges(&a.difference(&b)), vec![-10..-5, 8..12]);
+    assert_eq!(ranges(&b.difference(&a)), vec![0..5, 15..20]);
+    assert_eq!(a.intersection(&IntervalSet::new()), IntervalSet::new());
+    assert_eq!(a.cardinality(), 20);
+    assert_eq!(b.values().take(3).collect::<Vec<i64>>(), vec![-5, -4, -3]);
+  }
+
+  #[test]
+  fn splitting_cuts_only_at_boundaries_inside_a_range() {
+    let set: IntervalSet<u64> = [0..10, 20..30].into_iter().collect();
+
+    assert_eq!(set.split_by([25, 5, 10, 0, 50, 5]), vec![0..5, 5..10, 20..25, 25..30]);
+    assert_eq!(set.split_by([]), vec![0..10, 20..30]);
+  }
+}
@@ -0,0 +1,300 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// A minimal JSON value, enough for the runner's baseline and report files.
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonValue {
+  Null,
+  Bool(bool),
+  Integer(i64),
+  Float(f64),
+  String(String),
+  Array(Vec<JsonValue>),
+  Object(Vec<(String, JsonValue)>),
+}
+
+impl JsonValue {
+  pub fn get(self: &Self, key: &str) -> Option<&JsonValue> {
+    match self {
+      JsonValue::Object(fields) => fields.iter().find(|(name, _)| name == key).map(|(_, value)| value),
+      _ => None,
+    }
+  }
+
+  pub fn as_i64(self: &Self) -> Option<i64> {
+    match self {
+      JsonValue::Integer(value) => Some(*value),
+      _ => None,
+    }
+  }
+
+  pub fn as_str(self: &Self) -> Option<&str> {
+    match self {
+      JsonValue::String(value) => Some(value),
+      _ => None,
+    }
+  }
+
+  pub fn as_array(self: &Self) -> Option<&Vec<JsonValue>> {
+    match self {
+      JsonValue::Array(values) => Some(values),
+      _ => None,
+    }
+  }
+}
+
+impl From<&str> for JsonValue {
+  fn from(value: &str) -> Self {
+    JsonValue::String(value.to_string())
+  }
+}
+
+impl From<String> for JsonValue {
+  fn from(value: String) -> Self {
+    JsonValue::String(value)
+  }
+}
+
+impl From<i64> for JsonValue {
+  fn from(value: i64) -> Self {
+    JsonValue::Integer(value)
+  }
+}
+
+impl From<u64> for JsonValue {
+  fn from(value: u64) -> Self {
+    JsonValue::Integer(value as i64)
+  }
+}
+
+impl From<u8> for JsonValue {
+  fn from(value: u8) -> Self {
+    JsonValue::Integer(value as i64)
+  }
+}
+
+impl fmt::Display for JsonValue {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      JsonValue::Null => write!(f, "null"),
+      JsonValue::Bool(value) => write!(f, "{}", value),
+      JsonValue::Integer(value) => write!(f, "{}", value),
+      JsonValue::Float(value) => write!(f, "{}", value),
+      JsonValue::String(value) => write_escaped(f, value),
+      JsonValue::Array(values) => {
+        write!(f, "[")?;
+        for (index, value) in values.iter().enumerate() {
+          if index > 0 {
+            write!(f, ",")?;
+          }
+          write!(f, "{}", value)?;
+        }
+        write!(f, "]")
+      },
+      JsonValue::Object(fields) => {
+        write!(f, "{{")?;
+        for (index, (name, value)) in fields.iter().enumerate() {
+          if index > 0 {
+            write!(f, ",")?;
+          }
+          write_escaped(f, name)?;
+          write!(f, ":{}", value)?;
+        }
+        write!(f, "}}")
+      },
+    }
+  }
+}
+
+fn write_escaped(f: &mut fmt::Formatter<'_>, value: &str) -> fmt::Result {
+  write!(f, "\"")?;
+  for char in value.chars() {
+    match char {
+      '"' => write!(f, "\\\"")?,
+      '\\' => write!(f, "\\\\")?,
+      '\n' => write!(f, "\\n")?,
+      '\r' => write!(f, "\\r")?,
+      '\t' => write!(f, "\\t")?,
+      c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+      c => write!(f, "{}", c)?,
+    }
+  }
+  write!(f, "\"")
+}
+
+impl FromStr for JsonValue {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let mut parser = Parser { chars: s.char_indices().peekable() };
+
+    let value = parser.parse_value()?;
+    parser.skip_whitespace();
+
+    if let Some((offset, _)) = parser.chars.peek() {
+      return Err(format!("Unexpected trailing characters at offset {}", offset));
+    }
+
+    Ok(value)
+  }
+}
+
+struct Parser<'a> {
+  chars: std::iter::Peekable<std::str::CharIndices<'a>>,
+}
+
+impl<'a> Parser<'a> {
+  fn skip_whitespace(self: &mut Self) {
+    while self.chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+  }
+
+  fn expect(self: &mut Self, expected: char) -> Result<(), String> {
+    self.skip_whitespace();
+    match self.chars.next() {
+      Some((_, c)) if c == expected => Ok(()),
+      Some((offset, c)) => Err(format!("Expected '{}' but found '{}' at offset {}", expected, c, offset)),
+      None => Err(format!("Expected '{}' but found the end of input", expected)),
+    }
+  }
+
+  fn expect_word(self: &mut Self, word: &str) -> Result<(), String> {
+    for expected in word.chars() {
+      self.expect(expected)?;
+    }
+    Ok(())
+  }
+
+  fn parse_value(self: &mut Self) -> Result<JsonValue, String> {
+    self.skip_whitespace();
+
+    match self.chars.peek().copied() {
+      Some((_, '{')) => self.parse_object(),
+      Some((_, '[')) => self.parse_array(),
+      Some((_, '"')) => self.parse_string().map(JsonValue::String),
+      Some((_, 't')) => self.expect_word("true").map(|_| JsonValue::Bool(true)),
+      Some((_, 'f')) => self.expect_word("false").map(|_| JsonValue::Bool(false)),
+      Some((_, 'n')) => self.expect_word("null").map(|_| JsonValue::Null),
+      Some((_, c)) if c == '-' || c.is_ascii_digit() => self.parse_number(),
+      Some((offset, c)) => Err(format!("Unexpected '{}' at offset {}", c, offset)),
+      None => Err("Unexpected end of input".to_string()),
+    }
+  }
+
+  fn parse_object(self: &mut Self) -> Result<JsonValue, String> {
+    self.expect('{')?;
+    let mut fields = vec![];
+
+    self.skip_whitespace();
+    if self.chars.next_if(|(_, c)| *c == '}').is_some() {
+      return Ok(JsonValue::Object(fields));
+    }
+
+    loop {
+      self.skip_whitespace();
+      let name = self.parse_string()?;
+      self.expect(':')?;
+      fields.push((name, self.parse_value()?));
+
+      self.skip_whitespace();
+      match self.chars.next() {
+        Some((_, ',')) => continue,
+        Some((_, '}')) => return Ok(JsonValue::Object(fields)),
+        Some((offset, c)) => return Err(format!("Expected ',' or '}}' but found '{}' at offset {}", c, offset)),
+        None => return Err("Unterminated object".to_string()),
+      }
+    }
+  }
+
+  fn parse_array(self: &mut Self) -> Result<JsonValue, String> {
+    self.expect('[')?;
+    let mut values = vec![];
+
+    self.skip_whitespace();
+    if self.chars.next_if(|(_, c)| *c == ']').is_some() {
+      return Ok(JsonValue::Array(values));
+    }
+
+    loop {
+      values.push(self.parse_value()?);
+
+      self.skip_whitespace();
+      match self.chars.next() {
+        Some((_, ',')) => continue,
+        Some((_, ']')) => return Ok(JsonValue::Array(values)),
+        Some((offset, c)) => return Err(format!("Expected ',' or ']' but found '{}' at offset {}", c, offset)),
+        None => return Err("Unterminated array".to_string()),
+      }
+    }
+  }
+
+  fn parse_string(self: &mut Self) -> Result<String, String> {
+    self.expect('"')?;
+    let mut value = String::new();
+
+    loop {
+      match self.chars.next() {
+        Some((_, '"')) => return Ok(value),
+        Some((offset, '\\')) => match self.chars.next() {
+          Some((_, '"')) => value.push('"'),
+          Some((_, '\\')) => value.push('\\'),
+          Some((_, '/')) => value.push('/'),
+          Some((_, 'n')) => value.push('\n'),
+          Some((_, 'r')) => value.push('\r'),
+          Some((_, 't')) => value.push('\t'),
+          Some((_, 'u')) => {
+            let code: String = (0..4).filter_map(|_| self.chars.next().map(|(_, c)| c)).collect();
+            let code = u32::from_str_radix(&code, 16).ok().and_then(char::from_u32);
+            value.push(code.ok_or_else(|| format!("Invalid unicode escape at offset {}", offset))?);
+          },
+          _ => return Err(format!("Invalid escape at offset {}", offset)),
+        },
+        Some((_, c)) => value.push(c),
+        None => return Err("Unterminated string".to_string()),
+      }
+    }
+  }
+
+  fn parse_number(self: &mut Self) -> Result<JsonValue, String> {
+    let mut text = String::new();
+
+    while let Some((_, c)) = self.chars.next_if(|(_, c)| c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E')) {
+      text.push(c);
+    }
+
+    if let Ok(value) = text.parse::<i64>() {
+      return Ok(JsonValue::Integer(value));
+    }
+
+    text.parse::<f64>().map(JsonValue::Float).map_err(|_| format!("Invalid number '{}'", text))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn values_round_trip() {
+    let value = JsonValue::Object(vec![
+      ("name".to_string(), "quote \" and \\ slash".into()),
+      ("count".to_string(), JsonValue::Integer(-42)),
+      ("ratio".to_string(), JsonValue::Float(1.5)),
+      ("items".to_string(), JsonValue::Array(vec![JsonValue::Null, JsonValue::Bool(true)])),
+      ("empty".to_string(), JsonValue::Object(vec![])),
+    ]);
+
+    let text = value.to_string();
+    assert_eq!(text, r#"{"name":"quote \" and \\ slash","count":-42,"ratio":1.5,"items":[null,true],"empty":{}}"#);
+    assert_eq!(JsonValue::from_str(&text), Ok(value));
+  }
+
+  #[test]
+  fn parses_whitespace_and_rejects_garbage() {
+    let value = JsonValue::from_str(" { \"a\" : [ 1 , 2 ] }\n").unwrap();
+    assert_eq!(value.get("a").and_then(|a| a.as_array()).map(|a| a.len()), Some(2));
+
+    assert!(JsonValue::from_str("{\"a\": }").is_err());
+    assert!(JsonValue::from_str("[1, 2").is_err());
+    assert!(JsonValue::from_str("1 2").is_err());
+  }
+}
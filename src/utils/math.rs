@@ -0,0 +1,229 @@
+//! Number theory helpers: gcd and lcm, modular arithmetic, the Chinese Remainder Theorem and a
+//! prime sieve. Anything that can overflow returns `None` instead of wrapping.
+
+use std::ops::{Div, Rem};
+
+/// The unsigned integers `gcd` and `lcm` work over.
+pub trait Unsigned: Copy + PartialEq + Rem<Output = Self> + Div<Output = Self> {
+  const ZERO: Self;
+  const ONE: Self;
+
+  fn checked_mul(self: Self, other: Self) -> Option<Self>;
+}
+
+macro_rules! impl_unsigned {
+  ($($type:ty),*) => {
+    $(
+      impl Unsigned for $type {
+        const ZERO: Self = 0;
+        const ONE: Self = 1;
+
+        fn checked_mul(self: Self, other: Self) -> Option<Self> {
+          <$type>::checked_mul(self, other)
+        }
+      }
+    )*
+  };
+}
+
+impl_unsigned!(u32, u64, u128, usize);
+
+pub fn gcd<T: Unsigned>(mut a: T, mut b: T) -> T {
+  while b != T::ZERO {
+    (a, b) = (b, a % b);
+  }
+
+  a
+}
+
+/// The least common multiple, `None` if it overflows. `lcm(0, n)` is 0.
+pub fn lcm<T: Unsigned>(a: T, b: T) -> Option<T> {
+  if a == T::ZERO || b == T::ZERO {
+    return Some(T::ZERO);
+  }
+
+  (a / gcd(a, b)).checked_mul(b)
+}
+
+/// The least common multiple of every value, 1 when there are none.
+pub fn lcm_all<T: Unsigned>(values: impl IntoIterator<Item = T>) -> Option<T> {
+  values.into_iter().try_fold(T::ONE, lcm)
+}
+
+/// Returns `(g, x, y)` with `g = gcd(a, b)` and `a * x + b * y = g`.
+pub fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+  let (mut old_r, mut r) = (a, b);
+  let (mut old_x, mut x) = (1, 0);
+  let (mut old_y, mut y) = (0, 1);
+
+  while r != 0 {
+    let quotient = old_r / r;
+    (old_r, r) = (r, old_r - quotient * r);
+    (old_x, x) = (x, old_x - quotient * x);
+    (old_y, y) = (y, old_y - quotient * y);
+  }
+
+  match old_r < 0 {
+    true => (-old_r, -old_x, -old_y),
+    false => (old_r, old_x, old_y),
+  }
+}
+
+/// The `x` in `0..modulus` with `a * x ≡ 1`, `None` if `a` and `modulus` share a factor.
+pub fn mod_inverse(a: u64, modulus: u64) -> Option<u64> {
+  if modulus == 0 {
+    return None;
+  }
+
+  let (g, x, _) = extended_gcd(a as i128, modulus as i128);
+
+  match g == 1 {
+    true => Some(x.rem_euclid(modulus as i128) as u64),
+    false => None,
+  }
+}
+
+/// `base` to the power of `exponent`, modulo `modulus`. Panics if `modulus` is 0.
+pub fn mod_pow(base: u64, mut exponent: u64, modulus: u64) -> u64 {
+  assert!(modulus > 0, "Modulus must be positive");
+
+  let modulus = modulus as u128;
+  let mut base = base as u128 % modulus;
+  let mut result = 1 % modulus;
+
+  while exponent > 0 {
+    if exponent & 1 == 1 {
+      result = result * base % modulus;
+    }
+    base = base * base % modulus;
+    exponent >>= 1;
+  }
+
+  result as u64
+}
+
+/// Solves `x ≡ residue (mod modulus)` for every pair at once, returning the smallest such `x` and
+/// the modulus it repeats with. The moduli don't have to be coprime. `None` if the congruences
+/// contradict each other or the combined modulus doesn't fit in a `u64`.
+pub fn crt(congruences: &[(u64, u64)]) -> Option<(u64, u64)> {
+  let mut residue: u64 = 0;
+  let mut modulus: u64 = 1;
+
+  for &(next_residue, next_modulus) in congruences {
+    if next_modulus == 0 {
+      return None;
+    }
+
+    let next_residue = next_residue % next_modulus;
+    let g = gcd(modulus, next_modulus);
+
+    // x = residue + modulus * k, so modulus * k ≡ next_residue - residue (mod next_modulus)
+    let difference = (next_residue as i128 - residue as i128).rem_euclid(next_modulus as i128) as u128;
+    if !difference.is_multiple_of(g as u128) {
+      return None;
+    }
+
+    let step = next_modulus / g;
+    let inverse = mod_inverse((modulus / g) % step, step)?;
+    let k = (difference / g as u128) * inverse as u128 % step as u128;
+
+    let combined = lcm(modulus, next_modulus)?;
+    residue = ((residue as u128 + modulus as u128 * k) % combined as u128) as u64;
+    modulus = combined;
+  }
+
+  Some((residue, modulus))
+}
+
+/// Which numbers up to a limit are prime, found with the sieve of Eratosthenes.
+#[derive(Debug, Clone)]
+pub struct Sieve {
+  is_prime: Vec<bool>,
+}
+
+impl Sieve {
+  /// Sieves every number up to and including `limit`.
+  pub fn new(limit: usize) -> Self {
+    let mut is_prime = vec![true; limit + 1];
+    is_prime[0] = false;
+    if limit >= 1 {
+      is_prime[1] = false;
+    }
+
+    let mut number = 2;
+    while number * number <= limit {
+      if is_prime[number] {
+        for multiple in (number * number..=limit).step_by(number) {
+          is_prime[multiple] = false;
+        }
+      }
+      number += 1;
+    }
+
+    Sieve { is_prime }
+  }
+
+  pub fn limit(self: &Self) -> usize {
+    self.is_prime.len() - 1
+  }
+
+  /// Whether `number` is prime. Panics if it is past the limit.
+  pub fn is_prime(self: &Self, number: usize) -> bool {
+    assert!(number <= self.limit(), "{} is past the sieve's limit of {}", number, self.limit());
+
+    self.is_prime[number]
+  }
+
+  pub fn primes(self: &Self) -> impl Iterator<Item = usize> + '_ {
+    self.is_prime.iter().enumerate().filter(|(_, prime)| **prime).map(|(number, _)| number)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn gcd_and_lcm_check_for_overflow() {
+    assert_eq!(gcd(12u64, 18), 6);
+    assert_eq!(gcd(0u64, 7), 7);
+    assert_eq!(lcm(4u64, 6), Some(12));
+    assert_eq!(lcm(0u64, 6), Some(0));
+    assert_eq!(lcm(u64::MAX, u64::MAX - 1), None);
+    assert_eq!(lcm(u64::MAX as u128, u64::MAX as u128 - 1), Some(u64::MAX as u128 * (u64::MAX as u128 - 1)));
+    assert_eq!(lcm_all([2u64, 3, 4, 5]), Some(60));
+    assert_eq!(lcm_all(Vec::<u64>::new()), Some(1));
+  }
+
+  #[test]
+  fn modular_arithmetic() {
+    let (g, x, y) = extended_gcd(240, 46);
+    assert_eq!(g, 2);
+    assert_eq!(240 * x + 46 * y, 2);
+
+    assert_eq!(mod_inverse(3, 11), Some(4));
+    assert_eq!(mod_inverse(6, 9), None);
+    assert_eq!(mod_pow(4, 13, 497), 445);
+    assert_eq!(mod_pow(u64::MAX, 2, u64::MAX - 1), 1);
+    assert_eq!(mod_pow(5, 0, 1), 0);
+  }
+
+  #[test]
+  fn crt_handles_moduli_that_share_factors() {
+    assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+    assert_eq!(crt(&[(3, 4), (5, 6)]), Some((11, 12)));
+    assert_eq!(crt(&[(1, 4), (2, 6)]), None);
+    assert_eq!(crt(&[]), Some((0, 1)));
+    assert_eq!(crt(&[(1, u64::MAX), (0, u64::MAX - 1)]), None);
+  }
+
+  #[test]
+  fn sieves_find_primes() {
+    let sieve = Sieve::new(30);
+
+    assert_eq!(sieve.primes().collect::<Vec<usize>>(), vec![2, 3, 5, 7, 11, 13, 17, 19, 23, 29]);
+    assert!(sieve.is_prime(29));
+    assert!(!sieve.is_prime(1));
+    assert_eq!(Sieve::new(0).primes().count(), 0);
+  }
+}
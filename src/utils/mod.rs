@@ -1 +1,18 @@
-pub mod file_utils;
\ No newline at end of file
+pub mod alloc;
+pub mod duration_utils;
+pub mod error;
+pub mod file_utils;
+pub mod geom;
+pub mod grid;
+pub mod hash;
+pub mod http;
+pub mod input;
+pub mod input_resolver;
+pub mod intervals;
+pub mod json;
+pub mod math;
+pub mod parser;
+pub mod rng;
+pub mod scan;
+pub mod thread_pool;
+pub mod trace;
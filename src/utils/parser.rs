@@ -0,0 +1,267 @@
+//! Small parser combinators for puzzle inputs.
+//!
+//! A parser is any `Fn(&str) -> ParseResult<T>` that takes what it matched off the front of its
+//! input and hands back the rest. Failures keep the slice they stopped at, so `complete` can turn
+//! them into an `AocError` with the line and column of the mismatch.
+
+use crate::utils::error::AocError;
+use crate::utils::scan;
+
+/// Where a parser stopped and what it wanted to see there.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Failure<'a> {
+  pub expected: String,
+  /// The input from the mismatch onwards.
+  pub at: &'a str,
+}
+
+impl<'a> Failure<'a> {
+  pub fn new(expected: impl Into<String>, at: &'a str) -> Self {
+    Failure { expected: expected.into(), at }
+  }
+
+  /// The error for this failure, positioned in `source`, which the parser was run on.
+  pub fn into_error(self: Self, source: &str) -> AocError {
+    let found = found(self.at);
+
+    let message = match found.is_empty() {
+      true => format!("Expected {}", self.expected),
+      false => format!("Expected {}, found '{}'", self.expected, found),
+    };
+
+    AocError::new(message, source, found)
+  }
+}
+
+/// The rest of the input and the parsed value, or where and why parsing failed.
+pub type ParseResult<'a, T> = Result<(&'a str, T), Failure<'a>>;
+
+pub trait Parser<'a, T>: Fn(&'a str) -> ParseResult<'a, T> {}
+
+impl<'a, T, F: Fn(&'a str) -> ParseResult<'a, T>> Parser<'a, T> for F {}
+
+/// Runs `parser` on the whole of `source`, allowing only whitespace after it.
+pub fn complete<'a, T>(source: &'a str, parser: impl Parser<'a, T>) -> Result<T, AocError> {
+  let (rest, value) = parser(source).map_err(|failure| failure.into_error(source))?;
+  let (rest, _) = whitespace(rest).map_err(|failure| failure.into_error(source))?;
+
+  match rest.is_empty() {
+    true => Ok(value),
+    false => Err(Failure::new("the end of the input", rest).into_error(source)),
+  }
+}
+
+/// Exactly `expected`.
+pub fn literal<'a>(expected: &'static str) -> impl Parser<'a, &'a str> {
+  move |input: &'a str| match input.strip_prefix(expected) {
+    Some(rest) => Ok((rest, &input[..expected.len()])),
+    None => Err(Failure::new(format!("'{}'", expected.escape_debug()), input)),
+  }
+}
+
+/// One or more characters matching `predicate`, described as `expected` when there are none.
+pub fn take_while1<'a>(expected: &'static str, predicate: impl Fn(char) -> bool) -> impl Parser<'a, &'a str> {
+  move |input: &'a str| {
+    let end = input.find(|c: char| !predicate(c)).unwrap_or(input.len());
+
+    match end > 0 {
+      true => Ok((&input[end..], &input[..end])),
+      false => Err(Failure::new(expected, input)),
+    }
+  }
+}
+
+/// Letters, digits and underscores, e.g. day 8's `AAA`.
+pub fn identifier(input: &str) -> ParseResult<'_, &str> {
+  take_while1("an identifier", |c| c.is_ascii_alphanumeric() || c == '_')(input)
+}
+
+/// A run of digits that fits in `T`.
+pub fn unsigned<T: TryFrom<u64>>(input: &str) -> ParseResult<'_, T> {
+  let (rest, digits) = take_while1("a number", |c| c.is_ascii_digit())(input)?;
+
+  match scan::parse_unsigned(digits.as_bytes()).and_then(|value| T::try_from(value).ok()) {
+    Some(value) => Ok((rest, value)),
+    None => Err(Failure::new(format!("a number that fits in {}", std::any::type_name::<T>()), input)),
+  }
+}
+
+/// Like `unsigned`, with an optional leading `-`.
+pub fn signed<T: TryFrom<i64>>(input: &str) -> ParseResult<'_, T> {
+  let number = recognize(pair(opt(literal("-")), take_while1("a number", |c| c.is_ascii_digit())));
+  let (rest, text) = number(input).map_err(|failure| Failure { at: input, ..failure })?;
+
+  match scan::parse_signed(text.as_bytes()).and_then(|value| T::try_from(value).ok()) {
+    Some(value) => Ok((rest, value)),
+    None => Err(Failure::new(format!("a number that fits in {}", std::any::type_name::<T>()), input)),
+  }
+}
+
+/// Optional spaces and tabs, staying on the same line.
+pub fn spaces(input: &str) -> ParseResult<'_, &str> {
+  let end = input.find(|c: char| c != ' ' && c != '\t').unwrap_or(input.len());
+  Ok((&input[end..], &input[..end]))
+}
+
+/// Optional whitespace of any kind, line breaks included.
+pub fn whitespace(input: &str) -> ParseResult<'_, &str> {
+  let end = input.find(|c: char| !c.is_whitespace()).unwrap_or(input.len());
+  Ok((&input[end..], &input[..end]))
+}
+
+/// `\n` or `\r\n`.
+pub fn line_ending(input: &str) -> ParseResult<'_, &str> {
+  alt(literal("\n"), literal("\r\n"))(input)
+}
+
+/// Zero or more `item`s with `separator` between them. A separator that isn't followed by an item
+/// is left for whatever comes next, but an item that fails partway through is an error.
+pub fn separated<'a, T, S>(item: impl Parser<'a, T>, separator: impl Parser<'a, S>) -> impl Parser<'a, Vec<T>> {
+  move |input: &'a str| {
+    let mut items = vec![];
+
+    let Some((mut rest, first)) = attempt(&item, input)? else {
+      return Ok((input, items));
+    };
+    items.push(first);
+
+    while let Ok((after, _)) = separator(rest) {
+      let Some((next, value)) = attempt(&item, after)? else {
+        break;
+      };
+
+      items.push(value);
+      rest = next;
+    }
+
+    Ok((rest, items))
+  }
+}
+
+pub fn map<'a, T, U>(parser: impl Parser<'a, T>, f: impl Fn(T) -> U) -> impl Parser<'a, U> {
+  move |input: &'a str| parser(input).map(|(rest, value)| (rest, f(value)))
+}
+
+/// `parser` if it matches, otherwise `None` without consuming anything. Failing partway through
+/// is still an error.
+pub fn opt<'a, T>(parser: impl Parser<'a, T>) -> impl Parser<'a, Option<T>> {
+  move |input: &'a str| match attempt(&parser, input)? {
+    Some((rest, value)) => Ok((rest, Some(value))),
+    None => Ok((input, None)),
+  }
+}
+
+/// `first`, or `second` if that fails. When both fail the one that got further is reported, or
+/// both expectations if they failed at the same place.
+pub fn alt<'a, T>(first: impl Parser<'a, T>, second: impl Parser<'a, T>) -> impl Parser<'a, T> {
+  move |input: &'a str| {
+    let first_failure = match first(input) {
+      Ok(result) => return Ok(result),
+      Err(failure) => failure,
+    };
+
+    second(input).map_err(|second_failure| match first_failure.at.len().cmp(&second_failure.at.len()) {
+      std::cmp::Ordering::Less => first_failure,
+      std::cmp::Ordering::Greater => second_failure,
+      std::cmp::Ordering::Equal => Failure::new(format!("{} or {}", first_failure.expected, second_failure.expected), first_failure.at),
+    })
+  }
+}
+
+pub fn pair<'a, A, B>(first: impl Parser<'a, A>, second: impl Parser<'a, B>) -> impl Parser<'a, (A, B)> {
+  move |input: &'a str| {
+    let (rest, a) = first(input)?;
+    let (rest, b) = second(rest)?;
+    Ok((rest, (a, b)))
+  }
+}
+
+/// `prefix` then `parser`, keeping what `parser` matched.
+pub fn preceded<'a, P, T>(prefix: impl Parser<'a, P>, parser: impl Parser<'a, T>) -> impl Parser<'a, T> {
+  map(pair(prefix, parser), |(_, value)| value)
+}
+
+/// `parser` then `suffix`, keeping what `parser` matched.
+pub fn terminated<'a, T, S>(parser: impl Parser<'a, T>, suffix: impl Parser<'a, S>) -> impl Parser<'a, T> {
+  map(pair(parser, suffix), |(value, _)| value)
+}
+
+pub fn delimited<'a, P, T, S>(prefix: impl Parser<'a, P>, parser: impl Parser<'a, T>, suffix: impl Parser<'a, S>) -> impl Parser<'a, T> {
+  preceded(prefix, terminated(parser, suffix))
+}
+
+/// The slice of input `parser` matched, instead of its value.
+pub fn recognize<'a, T>(parser: impl Parser<'a, T>) -> impl Parser<'a, &'a str> {
+  move |input: &'a str| {
+    let (rest, _) = parser(input)?;
+    Ok((rest, &input[..input.len() - rest.len()]))
+  }
+}
+
+/// Runs `parser`, turning a failure right at the start of `input` into `None`. A failure further
+/// in means the input was the right shape but wrong, so it is passed on.
+fn attempt<'a, T>(parser: &impl Parser<'a, T>, input: &'a str) -> Result<Option<(&'a str, T)>, Failure<'a>> {
+  match parser(input) {
+    Ok(result) => Ok(Some(result)),
+    Err(failure) if failure.at.len() < input.len() => Err(failure),
+    Err(_) => Ok(None),
+  }
+}
+
+/// What to quote after "found": the word or symbol at the start of `at`.
+fn found(at: &str) -> &str {
+  let Some(first) = at.chars().next() else {
+    return at;
+  };
+
+  let end = match first.is_alphanumeric() {
+    true => at.find(|c: char| !c.is_alphanumeric()).unwrap_or(at.len()),
+    false => first.len_utf8(),
+  };
+
+  &at[..end]
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn combinators_take_what_they_match() {
+    let node = pair(identifier, delimited(pair(spaces, literal("= (")), separated(identifier, pair(literal(","), spaces)), literal(")")));
+
+    assert_eq!(node("AAA = (BBB, CCC) rest"), Ok((" rest", ("AAA", vec!["BBB", "CCC"]))));
+    assert_eq!(separated(unsigned::<u8>, spaces)("1  2 3 |"), Ok((" |", vec![1, 2, 3])));
+    assert_eq!(signed::<i32>("-42x"), Ok(("x", -42)));
+    assert_eq!(signed::<i64>("-9223372036854775808"), Ok(("", i64::MIN)));
+    assert_eq!(opt(literal("-"))("5"), Ok(("5", None)));
+    assert_eq!(recognize(pair(unsigned::<u32>, literal(".5")))("12.5s"), Ok(("s", "12.5")));
+    assert_eq!(line_ending("\r\nnext"), Ok(("next", "\r\n")));
+    assert!(unsigned::<u8>("256").is_err());
+    assert!(signed::<i64>("-").is_err());
+  }
+
+  #[test]
+  fn failures_point_at_the_mismatch() {
+    let source = "Game 1: 3 blue\nGame 2: x red";
+    let color = alt(literal("red"), literal("blue"));
+    let cube = pair(terminated(unsigned::<u32>, spaces), color);
+    let game = separated(preceded(pair(literal("Game "), unsigned::<u32>), preceded(literal(": "), &cube)), line_ending);
+
+    let error = complete(source, game).unwrap_err();
+    assert_eq!(error.message, "Expected a number, found 'x'");
+    assert_eq!((error.line, error.column), (2, 9));
+
+    let error = complete("1 2 x", separated(unsigned::<u32>, spaces)).unwrap_err();
+    assert_eq!(error.message, "Expected the end of the input, found 'x'");
+    assert_eq!((error.line, error.column), (1, 5));
+
+    let error = complete("Game 2: 4 green", preceded(literal("Game 2: "), cube)).unwrap_err();
+    assert_eq!(error.message, "Expected 'red' or 'blue', found 'green'");
+    assert_eq!((error.line, error.column, error.text.as_str()), (1, 11, "green"));
+
+    let error = complete("12", pair(unsigned::<u32>, literal(":"))).unwrap_err();
+    assert_eq!(error.message, "Expected ':'");
+    assert_eq!((error.line, error.column), (1, 3));
+  }
+}
@@ -0,0 +1,74 @@
+use std::ops::Range;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A small seeded PRNG (SplitMix64). The same seed always gives the same sequence,
+/// which is all the fuzzer needs to make a failure reproducible.
+#[derive(Debug, Clone)]
+pub struct Rng {
+  state: u64,
+}
+
+impl Rng {
+  pub fn new(seed: u64) -> Self {
+    Rng { state: seed }
+  }
+
+  /// A seed that changes from run to run, for when none is given.
+  pub fn time_seed() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|elapsed| elapsed.as_nanos() as u64).unwrap_or(0)
+  }
+
+  pub fn next_u64(self: &mut Self) -> u64 {
+    self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+
+    let mut z = self.state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+  }
+
+  /// A number in `range`. Panics if the range is empty.
+  pub fn range(self: &mut Self, range: Range<u64>) -> u64 {
+    assert!(!range.is_empty(), "Can't pick from an empty range");
+
+    range.start + self.next_u64() % (range.end - range.start)
+  }
+
+  /// An index into a collection of `len` items.
+  pub fn index(self: &mut Self, len: usize) -> usize {
+    self.range(0..len as u64) as usize
+  }
+
+  pub fn pick<'a, T>(self: &mut Self, items: &'a [T]) -> &'a T {
+    &items[self.index(items.len())]
+  }
+
+  /// True roughly `percent` times out of a hundred.
+  pub fn chance(self: &mut Self, percent: u64) -> bool {
+    self.range(0..100) < percent
+  }
+
+  pub fn shuffle<T>(self: &mut Self, items: &mut [T]) {
+    for i in (1..items.len()).rev() {
+      let j = self.index(i + 1);
+      items.swap(i, j);
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn seeds_replay_the_same_sequence() {
+    let mut first = Rng::new(42);
+    let mut second = Rng::new(42);
+
+    let values: Vec<u64> = (0..5).map(|_| first.range(10..20)).collect();
+
+    assert_eq!(values, (0..5).map(|_| second.range(10..20)).collect::<Vec<u64>>());
+    assert!(values.iter().all(|value| (10..20).contains(value)));
+    assert_ne!(Rng::new(1).next_u64(), Rng::new(2).next_u64());
+  }
+}
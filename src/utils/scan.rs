@@ -0,0 +1,144 @@
+//! Borrowed scanners for pulling numbers and tokens out of an input without allocating.
+
+/// Parses a run of ASCII digits. `None` if it is empty, holds anything else or overflows.
+pub fn parse_unsigned(bytes: &[u8]) -> Option<u64> {
+  if bytes.is_empty() {
+    return None;
+  }
+
+  let mut value: u64 = 0;
+
+  for byte in bytes {
+    if !byte.is_ascii_digit() {
+      return None;
+    }
+    value = value.checked_mul(10)?.checked_add((byte - b'0') as u64)?;
+  }
+
+  Some(value)
+}
+
+/// Like `parse_unsigned`, with an optional leading `-`.
+pub fn parse_signed(bytes: &[u8]) -> Option<i64> {
+  match bytes.split_first() {
+    Some((b'-', digits)) => {
+      let magnitude = parse_unsigned(digits)?;
+      0i64.checked_sub_unsigned(magnitude)
+    },
+    _ => i64::try_from(parse_unsigned(bytes)?).ok(),
+  }
+}
+
+/// Walks a `&str` front to back, handing out slices of it.
+#[derive(Debug, Clone)]
+pub struct Scanner<'a> {
+  text: &'a str,
+  position: usize,
+}
+
+impl<'a> Scanner<'a> {
+  pub fn new(text: &'a str) -> Self {
+    Scanner { text, position: 0 }
+  }
+
+  /// Everything not scanned yet.
+  pub fn remaining(self: &Self) -> &'a str {
+    &self.text[self.position..]
+  }
+
+  /// The next run of non-whitespace, skipping any ASCII whitespace before it.
+  pub fn token(self: &mut Self) -> Option<&'a str> {
+    self.skip_while(|byte| byte.is_ascii_whitespace());
+
+    let start = self.position;
+    self.skip_while(|byte| !byte.is_ascii_whitespace());
+
+    match self.position > start {
+      true => Some(&self.text[start..self.position]),
+      false => None,
+    }
+  }
+
+  /// The next run of digits, skipping anything that isn't a digit before it.
+  pub fn digits(self: &mut Self) -> Option<&'a str> {
+    self.skip_while(|byte| !byte.is_ascii_digit());
+
+    let start = self.position;
+    self.skip_while(|byte| byte.is_ascii_digit());
+
+    match self.position > start {
+      true => Some(&self.text[start..self.position]),
+      false => None,
+    }
+  }
+
+  /// The next unsigned number, skipping anything that isn't a digit before it.
+  pub fn unsigned(self: &mut Self) -> Option<u64> {
+    parse_unsigned(self.digits()?.as_bytes())
+  }
+
+  /// The next number, counting a `-` directly before its digits as its sign.
+  pub fn signed(self: &mut Self) -> Option<i64> {
+    let digits = self.digits()?;
+    let start = self.text.len() - self.remaining().len() - digits.len();
+
+    let magnitude = parse_unsigned(digits.as_bytes())? as i128;
+    let value = match start > 0 && self.text.as_bytes()[start - 1] == b'-' {
+      true => -magnitude,
+      false => magnitude,
+    };
+
+    i64::try_from(value).ok()
+  }
+
+  fn skip_while(self: &mut Self, predicate: impl Fn(u8) -> bool) {
+    let bytes = self.text.as_bytes();
+    while self.position < bytes.len() && predicate(bytes[self.position]) {
+      self.position += 1;
+    }
+  }
+}
+
+/// Every unsigned number in `text`, ignoring whatever separates them.
+pub fn unsigned_numbers(text: &str) -> impl Iterator<Item = u64> + '_ {
+  let mut scanner = Scanner::new(text);
+  std::iter::from_fn(move || scanner.unsigned())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn numbers_parse_from_bytes() {
+    assert_eq!(parse_unsigned(b"1234"), Some(1234));
+    assert_eq!(parse_unsigned(b"18446744073709551615"), Some(u64::MAX));
+    assert_eq!(parse_unsigned(b"18446744073709551616"), None);
+    assert_eq!(parse_unsigned(b"12a"), None);
+    assert_eq!(parse_unsigned(b""), None);
+    assert_eq!(parse_signed(b"-42"), Some(-42));
+    assert_eq!(parse_signed(b"-9223372036854775808"), Some(i64::MIN));
+    assert_eq!(parse_signed(b"-"), None);
+  }
+
+  #[test]
+  fn scanners_borrow_from_the_input() {
+    let text = "Card  1: 41 48 | 83 -6";
+    let mut scanner = Scanner::new(text);
+
+    assert_eq!(scanner.token(), Some("Card"));
+    assert_eq!(scanner.unsigned(), Some(1));
+    assert_eq!(scanner.token(), Some(":"));
+    assert_eq!(scanner.remaining(), " 41 48 | 83 -6");
+
+    let token = scanner.token().unwrap();
+    assert_eq!(token.as_ptr(), text[9..].as_ptr());
+
+    assert_eq!(scanner.unsigned(), Some(48));
+    assert_eq!(scanner.signed(), Some(83));
+    assert_eq!(scanner.signed(), Some(-6));
+    assert_eq!(scanner.token(), None);
+
+    assert_eq!(unsigned_numbers("seeds: 79 14 55 13").collect::<Vec<u64>>(), vec![79, 14, 55, 13]);
+  }
+}
@@ -0,0 +1,93 @@
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+/// Guards the process-wide panic hook, so callers on different threads (tests run in parallel)
+/// can't restore each other's hooks.
+static PANIC_HOOK: Mutex<()> = Mutex::new(());
+
+pub type Job<'a, T> = Box<dyn FnOnce() -> T + Send + 'a>;
+
+/// Runs `jobs` on at most `workers` threads and returns their results in the order the jobs were given.
+/// A job that panics yields `Err` with the panic message instead of taking down the other jobs.
+pub fn run_bounded<'a, T: Send>(jobs: Vec<Job<'a, T>>, workers: usize) -> Vec<Result<T, String>> {
+  let job_count = jobs.len();
+  let jobs: Vec<Mutex<Option<Job<'a, T>>>> = jobs.into_iter().map(|job| Mutex::new(Some(job))).collect();
+  let results: Vec<Mutex<Option<Result<T, String>>>> = (0..job_count).map(|_| Mutex::new(None)).collect();
+  let next_job = AtomicUsize::new(0);
+
+  thread::scope(|scope| {
+    for _ in 0..workers.clamp(1, job_count.max(1)) {
+      scope.spawn(|| {
+        loop {
+          let index = next_job.fetch_add(1, Ordering::SeqCst);
+          if index >= job_count {
+            break;
+          }
+
+          let job = jobs[index].lock().unwrap().take().expect("Each job should only be taken once");
+          let result = panic::catch_unwind(AssertUnwindSafe(job)).map_err(panic_message);
+
+          *results[index].lock().unwrap() = Some(result);
+        }
+      });
+    }
+  });
+
+  results.into_iter()
+    .map(|result| result.into_inner().unwrap().expect("Every job should have run"))
+    .collect()
+}
+
+/// Runs `f` with the panic hook silenced, for callers that catch and report panics themselves.
+/// Not reentrant: `f` must not call it again.
+pub fn without_panic_output<T>(f: impl FnOnce() -> T) -> T {
+  let _guard = PANIC_HOOK.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+
+  let previous_hook = panic::take_hook();
+  panic::set_hook(Box::new(|_| {}));
+
+  let result = panic::catch_unwind(AssertUnwindSafe(f));
+
+  panic::set_hook(previous_hook);
+
+  result.unwrap_or_else(|payload| panic::resume_unwind(payload))
+}
+
+pub fn panic_message(payload: Box<dyn Any + Send>) -> String {
+  if let Some(message) = payload.downcast_ref::<&str>() {
+    return message.to_string();
+  }
+
+  if let Some(message) = payload.downcast_ref::<String>() {
+    return message.clone();
+  }
+
+  "unknown panic".to_string()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn results_keep_job_order_and_panics_are_isolated() {
+    let jobs: Vec<Job<u64>> = (0..10u64).map(|n| -> Job<u64> {
+      Box::new(move || {
+        if n == 3 {
+          panic!("job {} failed", n);
+        }
+        n * n
+      })
+    }).collect();
+
+    let results = without_panic_output(|| run_bounded(jobs, 3));
+
+    assert_eq!(results.len(), 10);
+    assert_eq!(results[2], Ok(4));
+    assert_eq!(results[3], Err("job 3 failed".to_string()));
+    assert_eq!(results[9], Ok(81));
+  }
+}
@@ -0,0 +1,184 @@
+//! Scoped timing spans, so a part's time can be split into parsing, building, solving and so on.
+//!
+//! Spans are only recorded inside `record` on the same thread, and only once tracing is enabled.
+//! Anywhere else, including threads a solution spawns, `span` is a no-op.
+
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use crate::utils::duration_utils::format_duration;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+  static RECORDER: RefCell<Option<Recorder>> = const { RefCell::new(None) };
+}
+
+pub fn enable() {
+  ENABLED.store(true, Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+  ENABLED.load(Ordering::Relaxed)
+}
+
+/// A finished span. Spans with the same name under the same parent are merged, adding up their
+/// time and counting how often they were entered.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceSpan {
+  pub name: &'static str,
+  pub count: u32,
+  pub total: Duration,
+  pub children: Vec<TraceSpan>,
+}
+
+struct Entry {
+  name: &'static str,
+  parent: Option<usize>,
+  count: u32,
+  total: Duration,
+}
+
+#[derive(Default)]
+struct Recorder {
+  entries: Vec<Entry>,
+  open: Vec<usize>,
+}
+
+impl Recorder {
+  fn enter(self: &mut Self, name: &'static str) {
+    let parent = self.open.last().copied();
+
+    let index = match self.entries.iter().position(|entry| entry.parent == parent && entry.name == name) {
+      Some(index) => index,
+      None => {
+        self.entries.push(Entry { name, parent, count: 0, total: Duration::ZERO });
+        self.entries.len() - 1
+      }
+    };
+
+    self.entries[index].count += 1;
+    self.open.push(index);
+  }
+
+  fn exit(self: &mut Self, elapsed: Duration) {
+    if let Some(index) = self.open.pop() {
+      self.entries[index].total += elapsed;
+    }
+  }
+
+  fn tree(self: &Self, parent: Option<usize>) -> Vec<TraceSpan> {
+    self.entries.iter().enumerate()
+      .filter(|(_, entry)| entry.parent == parent)
+      .map(|(index, entry)| TraceSpan { name: entry.name, count: entry.count, total: entry.total, children: self.tree(Some(index)) })
+      .collect()
+  }
+}
+
+/// Times everything until it is dropped, as a child of whichever span is open on this thread.
+#[must_use = "the span ends as soon as it is dropped"]
+pub struct Span {
+  start: Option<Instant>,
+}
+
+impl Drop for Span {
+  fn drop(&mut self) {
+    if let Some(start) = self.start {
+      let elapsed = start.elapsed();
+      RECORDER.with(|recorder| {
+        if let Some(recorder) = recorder.borrow_mut().as_mut() {
+          recorder.exit(elapsed);
+        }
+      });
+    }
+  }
+}
+
+/// Opens a span named `name`, e.g. `let _span = trace::span("build mappers");`.
+pub fn span(name: &'static str) -> Span {
+  let recording = RECORDER.with(|recorder| match recorder.borrow_mut().as_mut() {
+    Some(recorder) => {
+      recorder.enter(name);
+      true
+    },
+    None => false,
+  });
+
+  Span { start: recording.then(Instant::now) }
+}
+
+/// Runs `f` inside a root span named `name` and hands back the finished span tree, or `None`
+/// if tracing isn't enabled.
+pub fn record<T>(name: &'static str, f: impl FnOnce() -> T) -> (T, Option<TraceSpan>) {
+  if !is_enabled() {
+    return (f(), None);
+  }
+
+  let outer = RECORDER.with(|recorder| recorder.borrow_mut().replace(Recorder::default()));
+
+  let result = {
+    let _span = span(name);
+    f()
+  };
+
+  let recorder = RECORDER.with(|recorder| recorder.replace(outer)).expect("The recorder was installed above");
+
+  (result, recorder.tree(None).pop())
+}
+
+/// Prints the spans as an indented tree with their total time, and how often they ran if more than once.
+pub fn render(spans: &[TraceSpan]) -> String {
+  fn render_into(spans: &[TraceSpan], depth: usize, output: &mut String) {
+    for span in spans {
+      let label = format!("{}{}", "  ".repeat(depth), span.name);
+      let count = if span.count > 1 { format!(" ({}x)", span.count) } else { String::new() };
+
+      output.push_str(&format!("{:<32}{:>10}{}\n", label, format_duration(span.total), count));
+      render_into(&span.children, depth + 1, output);
+    }
+  }
+
+  let mut output = String::new();
+  render_into(spans, 0, &mut output);
+  output
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn spans_nest_and_merge_by_name() {
+    enable();
+
+    let (answer, trace) = record("solve", || {
+      for _ in 0..3 {
+        let _span = span("walk");
+        let _inner = span("step");
+      }
+      let _span = span("join threads");
+      42
+    });
+
+    let trace = trace.unwrap();
+    let shape: Vec<(&str, u32, usize)> = trace.children.iter().map(|span| (span.name, span.count, span.children.len())).collect();
+
+    assert_eq!(answer, 42);
+    assert_eq!(trace.name, "solve");
+    assert_eq!(shape, vec![("walk", 3, 1), ("join threads", 1, 0)]);
+    assert_eq!(trace.children[0].children[0].count, 3);
+    assert!(trace.total >= trace.children[0].total);
+
+    let rendered = render(&[trace]);
+    let labels: Vec<&str> = rendered.lines().map(|line| line.split_whitespace().next().unwrap()).collect();
+    assert_eq!(labels, vec!["solve", "walk", "step", "join"]);
+    assert!(rendered.lines().nth(1).unwrap().starts_with("  walk") && rendered.contains("(3x)"));
+  }
+
+  #[test]
+  fn spans_outside_a_recording_do_nothing() {
+    let span = span("parse");
+    assert!(span.start.is_none());
+  }
+}
@@ -0,0 +1,209 @@
+use std::env;
+use std::fs;
+use std::panic;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::thread;
+use std::time::SystemTime;
+
+use crate::cli::WatchOptions;
+use crate::solution::{Answer, RegisteredDay};
+use crate::utils::input_resolver::InputResolver;
+use crate::utils::json::JsonValue;
+use crate::utils::thread_pool;
+
+/// A part's answer from one pass, or why there isn't one.
+type PartOutcome = (u8, Result<Answer, String>);
+
+/// Modification times of every watched file, `None` for files that have gone missing.
+type Snapshot = Vec<(PathBuf, Option<SystemTime>)>;
+
+/// Polls the day's input files (and the executable with `--binary`), rerunning the day whenever
+/// one of them changes. Runs until interrupted.
+pub fn run(options: &WatchOptions, registry: &[RegisteredDay]) -> Result<(), String> {
+  let day = options.run.days.single().ok_or("watch needs a single --day")?;
+  let inputFile = options.run.input_for(day);
+
+  let executable = match options.binary {
+    true => Some(env::current_exe().map_err(|error| format!("Could not find the running executable: {}", error))?),
+    false => None,
+  };
+
+  if executable.is_none() && !registry.iter().any(|registered| registered.day == day) {
+    return Err(format!("Day {} is not registered", day));
+  }
+
+  println!("Watching day {} on {}, press Ctrl-C to stop", day, inputFile);
+
+  let mut previous: Vec<PartOutcome> = vec![];
+  let mut last_snapshot: Option<Snapshot> = None;
+
+  loop {
+    let mut watched = watched_inputs(InputResolver::current().directory(), day);
+    if !watched.contains(&PathBuf::from(&inputFile)) {
+      watched.push(PathBuf::from(&inputFile));
+    }
+    watched.extend(executable.iter().cloned());
+
+    let current = snapshot(&watched);
+
+    if last_snapshot.as_ref() != Some(&current) {
+      let changed = match &last_snapshot {
+        None => "first run".to_string(),
+        Some(last) => changed_files(last, &current).join(", "),
+      };
+      println!("\n== Day {} ({})", day, changed);
+
+      let outcomes = match &executable {
+        Some(executable) => run_executable(executable, options, day, &inputFile),
+        None => run_in_process(registry, options, day, &inputFile),
+      };
+
+      for (part, outcome) in outcomes.iter() {
+        let before = previous.iter().find(|(previous_part, _)| previous_part == part).map(|(_, outcome)| outcome);
+
+        match outcome {
+          Ok(answer) => println!("Day {} Part {}: {}{}", day, part, answer, compare(before, answer)),
+          Err(message) => println!("Day {} Part {} failed\n{}", day, part, message),
+        }
+      }
+
+      previous = outcomes;
+      last_snapshot = Some(current);
+    }
+
+    thread::sleep(options.interval);
+  }
+}
+
+/// Every `dayN*.txt` file in the input directory, so example and variant edits trigger a rerun too.
+fn watched_inputs(directory: &str, day: u8) -> Vec<PathBuf> {
+  let Ok(entries) = fs::read_dir(directory) else {
+    return vec![];
+  };
+
+  let mut inputs: Vec<PathBuf> = entries
+    .filter_map(|entry| entry.ok())
+    .map(|entry| entry.path())
+    .filter(|path| path.file_name().and_then(|name| name.to_str()).is_some_and(|name| is_day_input(name, day)))
+    .collect();
+
+  inputs.sort();
+  inputs
+}
+
+/// Whether `name` is one of `day`'s input files. `day1test.txt` belongs to day 1 but `day12part1.txt` doesn't.
+fn is_day_input(name: &str, day: u8) -> bool {
+  let Some(rest) = name.strip_prefix(&format!("day{}", day)) else {
+    return false;
+  };
+
+  rest.ends_with(".txt") && !rest.starts_with(|c: char| c.is_ascii_digit())
+}
+
+fn snapshot(paths: &[PathBuf]) -> Snapshot {
+  paths.iter()
+    .map(|path| (path.clone(), fs::metadata(path).and_then(|metadata| metadata.modified()).ok()))
+    .collect()
+}
+
+fn changed_files(last: &Snapshot, current: &Snapshot) -> Vec<String> {
+  let name = |path: &Path| path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+
+  let mut changed: Vec<String> = current.iter()
+    .filter(|entry| !last.contains(entry))
+    .map(|(path, _)| name(path))
+    .collect();
+
+  changed.extend(last.iter()
+    .filter(|(path, _)| !current.iter().any(|(current_path, _)| current_path == path))
+    .map(|(path, _)| format!("{} removed", name(path))));
+
+  changed
+}
+
+/// Describes how an answer differs from the previous pass.
+fn compare(previous: Option<&Result<Answer, String>>, answer: &Answer) -> String {
+  match previous {
+    None => String::new(),
+    Some(Ok(previous)) if previous == answer => " (unchanged)".to_string(),
+    Some(Ok(previous)) => format!(" (was {})", previous),
+    Some(Err(_)) => " (previously failed)".to_string(),
+  }
+}
+
+fn run_in_process(registry: &[RegisteredDay], options: &WatchOptions, day: u8, inputFile: &str) -> Vec<PartOutcome> {
+  let Some(registered) = registry.iter().find(|registered| registered.day == day) else {
+    return vec![];
+  };
+
+  let parts: Vec<u8> = [1, 2].into_iter().filter(|part| options.run.runs_part(*part)).collect();
+
+  let outcomes: Vec<Result<Answer, String>> = match panic::catch_unwind(|| (registered.timed)(Some(inputFile), &parts)) {
+    Ok(Ok(runs)) => runs.into_iter().map(|run| Ok(run.answer)).collect(),
+    Ok(Err(error)) => vec![Err(error.to_string()); parts.len()],
+    Err(payload) => vec![Err(format!("panicked: {}", thread_pool::panic_message(payload))); parts.len()],
+  };
+
+  parts.into_iter().zip(outcomes).collect()
+}
+
+/// Runs the day in a fresh process of `executable`, reading its answers back from `--format json`.
+fn run_executable(executable: &Path, options: &WatchOptions, day: u8, inputFile: &str) -> Vec<PartOutcome> {
+  let parts: Vec<u8> = [1, 2].into_iter().filter(|part| options.run.runs_part(*part)).collect();
+  let failed = |message: String| parts.iter().map(|part| (*part, Err(message.clone()))).collect();
+
+  let mut command = Command::new(executable);
+  command.args(["run", "--day", &day.to_string(), "--input", inputFile, "--format", "json"]);
+  if let Some(part) = options.run.part {
+    command.args(["--part", &part.to_string()]);
+  }
+
+  let output = match command.output() {
+    Ok(output) => output,
+    Err(error) => return failed(format!("Could not start {}: {}", executable.display(), error)),
+  };
+
+  let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+
+  let json = match String::from_utf8_lossy(&output.stdout).parse::<JsonValue>() {
+    Ok(json) => json,
+    Err(_) => return failed(stderr),
+  };
+
+  let results = json.get("results").and_then(|results| results.as_array()).cloned().unwrap_or_default();
+
+  parts.iter().map(|part| {
+    let result = results.iter().find(|result| result.get("part").and_then(|p| p.as_i64()) == Some(*part as i64));
+
+    let outcome = result.and_then(|result| result.get("answer"))
+      .and_then(Answer::from_json)
+      .ok_or_else(|| stderr.clone());
+
+    (*part, outcome)
+  }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn day_inputs_do_not_include_later_days() {
+    assert!(is_day_input("day1part1.txt", 1));
+    assert!(is_day_input("day1test2.txt", 1));
+    assert!(!is_day_input("day12part1.txt", 1));
+    assert!(!is_day_input("day1part1.txt.swp", 1));
+    assert!(!is_day_input("answers.toml", 1));
+  }
+
+  #[test]
+  fn answers_are_compared_with_the_previous_pass() {
+    let answer = Answer::Number(42);
+
+    assert_eq!(compare(None, &answer), "");
+    assert_eq!(compare(Some(&Ok(Answer::Number(42))), &answer), " (unchanged)");
+    assert_eq!(compare(Some(&Ok(Answer::Number(41))), &answer), " (was 41)");
+    assert_eq!(compare(Some(&Err("bad input".to_string())), &answer), " (previously failed)");
+  }
+}